@@ -0,0 +1,103 @@
+//! Loading a large book the way `text_load` used to, into a `String` per word, against the shared buffer with
+//! spans. Prints the memory each keeps alive before timing them:
+//!
+//!     cargo bench --bench text
+
+use std::{
+  alloc::{GlobalAlloc, Layout, System},
+  hint::black_box,
+  path::Path,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+#[allow(dead_code, unused_imports)]
+#[path = "../src/text.rs"]
+mod text;
+
+use text::{Buffer, Text};
+
+const BOOK: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/williamMakepeaceThackeray_vanityFair.txt");
+
+/// Keeps count of the bytes allocated on the heap.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    System.dealloc(ptr, layout)
+  }
+
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+    ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    System.realloc(ptr, layout, new_size)
+  }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// How `text_load` stored a book before: the whole file, then a copy of every word.
+fn load_strings(path: &Path) -> (String, Vec<String>) {
+  let book = std::fs::read_to_string(path).unwrap();
+  let words = book.split_whitespace().map(|s| s.to_string()).collect();
+  (book, words)
+}
+
+fn load_spans(path: &Path) -> Text {
+  let mut text = Text::new(Arc::new(Buffer::open(path).unwrap()));
+  let tokens = text::tokenize(text.source(), false);
+  text.extend(&tokens.spans);
+  text
+}
+
+fn first_batch(path: &Path) -> Text {
+  let mut text = Text::new(Arc::new(Buffer::open(path).unwrap()));
+  let tokens = text::batches(text.source(), false).next().unwrap();
+  text.extend(&tokens.spans);
+  text
+}
+
+/// Heap bytes kept alive by the value `build` returns.
+fn retained<T>(build: impl FnOnce() -> T) -> usize {
+  let before = ALLOCATED.load(Ordering::Relaxed);
+  let value = build();
+  let after = ALLOCATED.load(Ordering::Relaxed);
+  drop(value);
+  after - before
+}
+
+fn bench(c: &mut Criterion) {
+  let path = Path::new(BOOK);
+  let mb = |bytes: usize| bytes as f64 / 1e6;
+  let size = std::fs::metadata(path).unwrap().len() as usize;
+  println!("{}: {:.1} MB", path.file_name().unwrap().to_string_lossy(), mb(size));
+  println!("  String per word: {:.1} MB", mb(retained(|| load_strings(path))));
+  println!("  spans:           {:.1} MB", mb(retained(|| load_spans(path))));
+
+  let mut group = c.benchmark_group("load");
+  group.sample_size(10);
+  group.bench_function("string per word", |b| b.iter(|| black_box(load_strings(path))));
+  group.bench_function("spans", |b| b.iter(|| black_box(load_spans(path))));
+  group.finish();
+
+  let mut group = c.benchmark_group("first word");
+  group.bench_function("string per word", |b| b.iter(|| black_box(load_strings(path).1[0].len())));
+  group.bench_function("first batch", |b| b.iter(|| black_box(first_batch(path)[0].len())));
+  group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -1,39 +1,278 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use serde::{
-  de::{self, Deserializer, Visitor},
+  de::{self, Deserializer, MapAccess, Visitor},
   Deserialize, Serialize,
 };
+use serde_json::Value as JsonValue;
 
-//// ANCHOR: action_enum
+use crate::app::Mode;
+
+// ANCHOR: action_enum
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Action {
   Tick,
   Render,
+  Redraw,
   Resize(u16, u16),
   Suspend,
   Resume,
   Quit,
+  ReloadConfig,
+  FocusLost,
+  FocusGained,
   Refresh,
   Error(String),
+  Warning(String),
+  Info(String),
+  ToggleErrorLog,
+  ToggleLogPane,
   Help,
   ToggleShowHelp,
+  PushMode(Mode),
+  PopMode,
   ScheduleIncrementText,
   ScheduleDecrementText,
   ScheduleTextLoad,
   ScheduleSreadText,
+  Play,
+  Pause,
   IncrementText(usize),
   DecrementText(usize),
+  NextParagraph(usize),
+  PreviousParagraph(usize),
+  Seek(usize),
+  PendingCount(usize),
+  CycleTheme,
+  SetTheme(String),
+  ToggleBigFont,
+  IncreaseWpm(u32),
+  DecreaseWpm(u32),
+  SetWpm(u32),
+  EditBookSettings,
+  ResetBookSettings,
+  TogglePoetry,
+  ToggleContext,
   TextLoad(),
+  TextOpen(String),
+  TextLoaded,
+  TextPaste(String),
   SreadText(usize),
   CompleteInput(String),
   EnterNormal,
   EnterInsert,
   EnterProcessing,
   ExitProcessing,
+  ResumeCountdown(u64),
   Update,
 }
-//// ANCHOR_END: action_enum
+// ANCHOR_END: action_enum
+
+const ACTION_SIGNATURES: &[&str] = &[
+  "Tick",
+  "Render",
+  "Redraw",
+  "Resize(u16, u16)",
+  "Suspend",
+  "Resume",
+  "Quit",
+  "ReloadConfig",
+  "FocusLost",
+  "FocusGained",
+  "Refresh",
+  "Error(String)",
+  "Warning(String)",
+  "Info(String)",
+  "ToggleErrorLog",
+  "ToggleLogPane",
+  "Help",
+  "ToggleShowHelp",
+  "PushMode(Mode)",
+  "PopMode",
+  "ScheduleIncrementText",
+  "ScheduleDecrementText",
+  "ScheduleTextLoad",
+  "ScheduleSreadText",
+  "Play",
+  "Pause",
+  "IncrementText(usize)",
+  "DecrementText(usize)",
+  "NextParagraph(usize)",
+  "PreviousParagraph(usize)",
+  "Seek(usize)",
+  "PendingCount(usize)",
+  "CycleTheme",
+  "SetTheme(String)",
+  "ToggleBigFont",
+  "IncreaseWpm(u32)",
+  "DecreaseWpm(u32)",
+  "SetWpm(u32)",
+  "EditBookSettings",
+  "ResetBookSettings",
+  "TogglePoetry",
+  "ToggleContext",
+  "TextLoad()",
+  "TextOpen(String)",
+  "TextLoaded",
+  "TextPaste(String)",
+  "SreadText(usize)",
+  "CompleteInput(String)",
+  "EnterNormal",
+  "EnterInsert",
+  "EnterProcessing",
+  "ExitProcessing",
+  "ResumeCountdown(u64)",
+  "Update",
+];
+
+impl Action {
+  /// Applies a vim-style count prefix, e.g. `25j` or `3}`. Actions that take no count are returned unchanged.
+  pub fn with_count(self, count: usize) -> Action {
+    match self {
+      Action::ScheduleIncrementText => Action::IncrementText(count),
+      Action::ScheduleDecrementText => Action::DecrementText(count),
+      Action::IncrementText(i) => Action::IncrementText(i.saturating_mul(count)),
+      Action::DecrementText(i) => Action::DecrementText(i.saturating_mul(count)),
+      Action::NextParagraph(i) => Action::NextParagraph(i.saturating_mul(count)),
+      Action::PreviousParagraph(i) => Action::PreviousParagraph(i.saturating_mul(count)),
+      Action::IncreaseWpm(i) => Action::IncreaseWpm(i.saturating_mul(u32::try_from(count).unwrap_or(u32::MAX))),
+      Action::DecreaseWpm(i) => Action::DecreaseWpm(i.saturating_mul(u32::try_from(count).unwrap_or(u32::MAX))),
+      action => action,
+    }
+  }
+
+  fn from_parts(name: &str, args: &[String]) -> Result<Self, String> {
+    let signature = ACTION_SIGNATURES
+      .iter()
+      .find(|sig| sig.split('(').next() == Some(name))
+      .ok_or_else(|| format!("Unknown action `{name}`, expected one of: {}", ACTION_SIGNATURES.join(", ")))?;
+
+    fn arg<T: FromStr>(signature: &str, args: &[String], i: usize) -> Result<T, String>
+    where
+      T::Err: fmt::Display,
+    {
+      let raw = args.get(i).ok_or_else(|| format!("Missing argument for `{signature}`"))?;
+      raw.trim().parse().map_err(|e| format!("Invalid argument `{raw}` for `{signature}`: {e}"))
+    }
+
+    let expected = signature.matches(',').count() + usize::from(!signature.ends_with("()") && signature.contains('('));
+    if args.len() != expected {
+      return Err(format!("`{signature}` takes {expected} argument(s) but {} were given", args.len()));
+    }
+
+    let action = match name {
+      "Tick" => Action::Tick,
+      "Render" => Action::Render,
+      "Redraw" => Action::Redraw,
+      "Resize" => Action::Resize(arg(signature, args, 0)?, arg(signature, args, 1)?),
+      "Suspend" => Action::Suspend,
+      "Resume" => Action::Resume,
+      "Quit" => Action::Quit,
+      "ReloadConfig" => Action::ReloadConfig,
+      "FocusLost" => Action::FocusLost,
+      "FocusGained" => Action::FocusGained,
+      "Refresh" => Action::Refresh,
+      "Error" => Action::Error(args[0].clone()),
+      "Warning" => Action::Warning(args[0].clone()),
+      "Info" => Action::Info(args[0].clone()),
+      "ToggleErrorLog" => Action::ToggleErrorLog,
+      "ToggleLogPane" => Action::ToggleLogPane,
+      "Help" => Action::Help,
+      "ToggleShowHelp" => Action::ToggleShowHelp,
+      "PushMode" => Action::PushMode(arg(signature, args, 0)?),
+      "PopMode" => Action::PopMode,
+      "ScheduleIncrementText" => Action::ScheduleIncrementText,
+      "ScheduleDecrementText" => Action::ScheduleDecrementText,
+      "ScheduleTextLoad" => Action::ScheduleTextLoad,
+      "ScheduleSreadText" => Action::ScheduleSreadText,
+      "Play" => Action::Play,
+      "Pause" => Action::Pause,
+      "IncrementText" => Action::IncrementText(arg(signature, args, 0)?),
+      "DecrementText" => Action::DecrementText(arg(signature, args, 0)?),
+      "NextParagraph" => Action::NextParagraph(arg(signature, args, 0)?),
+      "PreviousParagraph" => Action::PreviousParagraph(arg(signature, args, 0)?),
+      "Seek" => Action::Seek(arg(signature, args, 0)?),
+      "PendingCount" => Action::PendingCount(arg(signature, args, 0)?),
+      "CycleTheme" => Action::CycleTheme,
+      "SetTheme" => Action::SetTheme(args[0].clone()),
+      "ToggleBigFont" => Action::ToggleBigFont,
+      "IncreaseWpm" => Action::IncreaseWpm(arg(signature, args, 0)?),
+      "DecreaseWpm" => Action::DecreaseWpm(arg(signature, args, 0)?),
+      "SetWpm" => Action::SetWpm(arg(signature, args, 0)?),
+      "EditBookSettings" => Action::EditBookSettings,
+      "ResetBookSettings" => Action::ResetBookSettings,
+      "TogglePoetry" => Action::TogglePoetry,
+      "ToggleContext" => Action::ToggleContext,
+      "TextLoad" => Action::TextLoad(),
+      "TextOpen" => Action::TextOpen(args[0].clone()),
+      "TextLoaded" => Action::TextLoaded,
+      "TextPaste" => Action::TextPaste(args[0].clone()),
+      "SreadText" => Action::SreadText(arg(signature, args, 0)?),
+      "CompleteInput" => Action::CompleteInput(args[0].clone()),
+      "EnterNormal" => Action::EnterNormal,
+      "EnterInsert" => Action::EnterInsert,
+      "EnterProcessing" => Action::EnterProcessing,
+      "ExitProcessing" => Action::ExitProcessing,
+      "ResumeCountdown" => Action::ResumeCountdown(arg(signature, args, 0)?),
+      "Update" => Action::Update,
+      _ => unreachable!("`{name}` is listed in ACTION_SIGNATURES"),
+    };
+    Ok(action)
+  }
+}
+
+impl FromStr for Action {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.trim();
+    let (name, inner) = match s.split_once('(') {
+      Some((name, rest)) => {
+        let inner = rest.strip_suffix(')').ok_or_else(|| format!("Invalid action `{s}`: missing closing `)`"))?;
+        (name.trim(), Some(inner))
+      },
+      None => (s, None),
+    };
+    // String arguments are taken verbatim, everything else is comma separated.
+    let takes_string = ACTION_SIGNATURES.iter().any(|sig| sig.strip_prefix(name) == Some("(String)"));
+    let args = match inner {
+      Some(inner) if takes_string => vec![inner.to_string()],
+      Some(inner) if inner.trim().is_empty() => vec![],
+      Some(inner) => inner.split(',').map(|a| a.trim().to_string()).collect(),
+      None => vec![],
+    };
+    Action::from_parts(name, &args)
+  }
+}
+
+impl fmt::Display for Action {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Action::Resize(w, h) => write!(f, "Resize({w}, {h})"),
+      Action::Error(s) => write!(f, "Error({s})"),
+      Action::Warning(s) => write!(f, "Warning({s})"),
+      Action::Info(s) => write!(f, "Info({s})"),
+      Action::IncrementText(i) => write!(f, "IncrementText({i})"),
+      Action::DecrementText(i) => write!(f, "DecrementText({i})"),
+      Action::NextParagraph(i) => write!(f, "NextParagraph({i})"),
+      Action::PreviousParagraph(i) => write!(f, "PreviousParagraph({i})"),
+      Action::Seek(i) => write!(f, "Seek({i})"),
+      Action::PendingCount(i) => write!(f, "PendingCount({i})"),
+      Action::PushMode(mode) => write!(f, "PushMode({mode})"),
+      Action::SetTheme(s) => write!(f, "SetTheme({s})"),
+      Action::IncreaseWpm(i) => write!(f, "IncreaseWpm({i})"),
+      Action::DecreaseWpm(i) => write!(f, "DecreaseWpm({i})"),
+      Action::SetWpm(i) => write!(f, "SetWpm({i})"),
+      Action::TextLoad() => write!(f, "TextLoad()"),
+      Action::TextOpen(s) => write!(f, "TextOpen({s})"),
+      Action::TextPaste(s) => write!(f, "TextPaste({s})"),
+      Action::SreadText(i) => write!(f, "SreadText({i})"),
+      Action::CompleteInput(s) => write!(f, "CompleteInput({s})"),
+      Action::ResumeCountdown(i) => write!(f, "ResumeCountdown({i})"),
+      _ => write!(f, "{self:?}"),
+    }
+  }
+}
 
 impl<'de> Deserialize<'de> for Action {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -53,40 +292,217 @@ impl<'de> Deserialize<'de> for Action {
       where
         E: de::Error,
       {
-        match value {
-          "Tick" => Ok(Action::Tick),
-          "Render" => Ok(Action::Render),
-          "Suspend" => Ok(Action::Suspend),
-          "Resume" => Ok(Action::Resume),
-          "Quit" => Ok(Action::Quit),
-          "Refresh" => Ok(Action::Refresh),
-          "Help" => Ok(Action::Help),
-          "ScheduleIncrementText" => Ok(Action::ScheduleIncrementText),
-          "ScheduleDecrementText" => Ok(Action::ScheduleDecrementText),
-          "ScheduleTextLoad" => Ok(Action::ScheduleTextLoad),
-          "ScheduleSreadText" => Ok(Action::ScheduleSreadText),
-          "ToggleShowHelp" => Ok(Action::ToggleShowHelp),
-          "EnterInsert" => Ok(Action::EnterInsert),
-          "EnterNormal" => Ok(Action::EnterNormal),
-          data if data.starts_with("Error(") => {
-            let error_msg = data.trim_start_matches("Error(").trim_end_matches(")");
-            Ok(Action::Error(error_msg.to_string()))
-          },
-          data if data.starts_with("Resize(") => {
-            let parts: Vec<&str> = data.trim_start_matches("Resize(").trim_end_matches(")").split(',').collect();
-            if parts.len() == 2 {
-              let width: u16 = parts[0].trim().parse().map_err(E::custom)?;
-              let height: u16 = parts[1].trim().parse().map_err(E::custom)?;
-              Ok(Action::Resize(width, height))
-            } else {
-              Err(E::custom(format!("Invalid Resize format: {}", value)))
-            }
-          },
-          _ => Err(E::custom(format!("Unknown Action variant: {}", value))),
-        }
+        value.parse().map_err(E::custom)
+      }
+
+      // The externally tagged form produced by `Serialize`, e.g. `{"IncrementText": 10}`.
+      fn visit_map<A>(self, mut map: A) -> Result<Action, A::Error>
+      where
+        A: MapAccess<'de>,
+      {
+        let (name, value) =
+          map.next_entry::<String, JsonValue>()?.ok_or_else(|| de::Error::custom("expected a single Action variant"))?;
+        let args = match value {
+          JsonValue::Null => vec![],
+          JsonValue::Array(values) => values.into_iter().map(json_arg).collect(),
+          value => vec![json_arg(value)],
+        };
+        Action::from_parts(&name, &args).map_err(de::Error::custom)
+      }
+    }
+
+    fn json_arg(value: JsonValue) -> String {
+      match value {
+        JsonValue::String(s) => s,
+        value => value.to_string(),
       }
     }
 
-    deserializer.deserialize_str(ActionVisitor)
+    deserializer.deserialize_any(ActionVisitor)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn all_actions() -> Vec<Action> {
+    vec![
+      Action::Tick,
+      Action::Render,
+      Action::Redraw,
+      Action::Resize(80, 24),
+      Action::Suspend,
+      Action::Resume,
+      Action::Quit,
+      Action::ReloadConfig,
+      Action::FocusLost,
+      Action::FocusGained,
+      Action::Refresh,
+      Action::Error("Failed to draw: (oops, twice)".into()),
+      Action::Warning("No hyphenation patterns for `xx`".into()),
+      Action::Info("Reloaded configuration".into()),
+      Action::ToggleErrorLog,
+      Action::ToggleLogPane,
+      Action::Help,
+      Action::ToggleShowHelp,
+      Action::PushMode(Mode::Library),
+      Action::PopMode,
+      Action::ScheduleIncrementText,
+      Action::ScheduleDecrementText,
+      Action::ScheduleTextLoad,
+      Action::ScheduleSreadText,
+      Action::Play,
+      Action::Pause,
+      Action::IncrementText(10),
+      Action::DecrementText(3),
+      Action::NextParagraph(1),
+      Action::PreviousParagraph(2),
+      Action::Seek(120),
+      Action::PendingCount(25),
+      Action::CycleTheme,
+      Action::SetTheme("solarized".into()),
+      Action::ToggleBigFont,
+      Action::IncreaseWpm(25),
+      Action::DecreaseWpm(50),
+      Action::SetWpm(400),
+      Action::EditBookSettings,
+      Action::ResetBookSettings,
+      Action::TogglePoetry,
+      Action::ToggleContext,
+      Action::TextLoad(),
+      Action::TextOpen("books/alice.txt".into()),
+      Action::TextLoaded,
+      Action::TextPaste("some, pasted (text)".into()),
+      Action::SreadText(1),
+      Action::CompleteInput("input".into()),
+      Action::EnterNormal,
+      Action::EnterInsert,
+      Action::EnterProcessing,
+      Action::ExitProcessing,
+      Action::ResumeCountdown(2),
+      Action::Update,
+    ]
+  }
+
+  // Fails to compile when a variant is added without extending `all_actions`.
+  fn covered(action: &Action) -> bool {
+    match action {
+      Action::Tick
+      | Action::Render
+      | Action::Redraw
+      | Action::Resize(..)
+      | Action::Suspend
+      | Action::Resume
+      | Action::Quit
+      | Action::ReloadConfig
+      | Action::FocusLost
+      | Action::FocusGained
+      | Action::Refresh
+      | Action::Error(_)
+      | Action::Warning(_)
+      | Action::Info(_)
+      | Action::ToggleErrorLog
+      | Action::ToggleLogPane
+      | Action::Help
+      | Action::ToggleShowHelp
+      | Action::PushMode(_)
+      | Action::PopMode
+      | Action::ScheduleIncrementText
+      | Action::ScheduleDecrementText
+      | Action::ScheduleTextLoad
+      | Action::ScheduleSreadText
+      | Action::Play
+      | Action::Pause
+      | Action::IncrementText(_)
+      | Action::DecrementText(_)
+      | Action::NextParagraph(_)
+      | Action::PreviousParagraph(_)
+      | Action::Seek(_)
+      | Action::PendingCount(_)
+      | Action::CycleTheme
+      | Action::SetTheme(_)
+      | Action::ToggleBigFont
+      | Action::IncreaseWpm(_)
+      | Action::DecreaseWpm(_)
+      | Action::SetWpm(_)
+      | Action::EditBookSettings
+      | Action::ResetBookSettings
+      | Action::TogglePoetry
+      | Action::ToggleContext
+      | Action::TextLoad()
+      | Action::TextOpen(_)
+      | Action::TextLoaded
+      | Action::TextPaste(_)
+      | Action::SreadText(_)
+      | Action::CompleteInput(_)
+      | Action::EnterNormal
+      | Action::EnterInsert
+      | Action::EnterProcessing
+      | Action::ExitProcessing
+      | Action::ResumeCountdown(_)
+      | Action::Update => true,
+    }
+  }
+
+  #[test]
+  fn test_all_actions_listed() {
+    let actions = all_actions();
+    assert!(actions.iter().all(covered));
+    assert_eq!(actions.len(), ACTION_SIGNATURES.len());
+  }
+
+  #[test]
+  fn test_string_round_trip() {
+    for action in all_actions() {
+      assert_eq!(action.to_string().parse::<Action>(), Ok(action));
+    }
+  }
+
+  #[test]
+  fn test_serde_round_trip() {
+    for action in all_actions() {
+      let json = serde_json::to_string(&action).unwrap();
+      assert_eq!(serde_json::from_str::<Action>(&json).unwrap(), action);
+    }
+  }
+
+  #[test]
+  fn test_config_strings() {
+    assert_eq!(json5::from_str::<Action>(r#""IncrementText(10)""#).unwrap(), Action::IncrementText(10));
+    assert_eq!(json5::from_str::<Action>(r#""Resize( 80 , 24 )""#).unwrap(), Action::Resize(80, 24));
+    assert_eq!(json5::from_str::<Action>(r#""TextLoad""#).unwrap(), Action::TextLoad());
+    assert_eq!(json5::from_str::<Action>(r#""EnterProcessing""#).unwrap(), Action::EnterProcessing);
+    assert_eq!(json5::from_str::<Action>(r#""PushMode(Home)""#).unwrap(), Action::PushMode(Mode::Reader));
+  }
+
+  #[test]
+  fn test_with_count() {
+    assert_eq!(Action::ScheduleIncrementText.with_count(25), Action::IncrementText(25));
+    assert_eq!(Action::ScheduleDecrementText.with_count(2), Action::DecrementText(2));
+    assert_eq!(Action::IncrementText(10).with_count(3), Action::IncrementText(30));
+    assert_eq!(Action::NextParagraph(1).with_count(3), Action::NextParagraph(3));
+    assert_eq!(Action::DecreaseWpm(25).with_count(4), Action::DecreaseWpm(100));
+    assert_eq!(Action::Quit.with_count(5), Action::Quit);
+  }
+
+  #[test]
+  fn test_errors() {
+    let err = "Jump".parse::<Action>().unwrap_err();
+    assert!(err.starts_with("Unknown action `Jump`, expected one of: Tick, Render"));
+    assert!(err.contains("IncrementText(usize)"));
+
+    let err = "IncrementText(ten)".parse::<Action>().unwrap_err();
+    assert!(err.starts_with("Invalid argument `ten` for `IncrementText(usize)`"));
+
+    let err = "IncrementText".parse::<Action>().unwrap_err();
+    assert_eq!(err, "`IncrementText(usize)` takes 1 argument(s) but 0 were given");
+
+    let err = "Quit(1)".parse::<Action>().unwrap_err();
+    assert_eq!(err, "`Quit` takes 0 argument(s) but 1 were given");
+
+    assert!("IncrementText(1".parse::<Action>().is_err());
   }
 }
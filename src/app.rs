@@ -1,158 +1,509 @@
-use color_eyre::eyre::Result;
-use crossterm::event::KeyEvent;
+use std::{fmt, str::FromStr, time::Duration};
+
+use color_eyre::eyre::{Result, WrapErr};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::prelude::Rect;
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use tokio::{
+  sync::{broadcast, mpsc},
+  task::JoinHandle,
+};
 
 use crate::{
   action::Action,
-  components::{fps::FpsCounter, home::Home, Component},
-  config::Config,
+  cli::Cli,
+  components::{
+    fps::FpsCounter, help::Help, home::Home, library::Library, logs::LogPane, notifications::Notifications,
+    settings::Settings, stats::Stats, Component,
+  },
+  config::{check_theme, next_theme, Config},
+  remote::{self, Event},
+  session::{self, Log, Recorder},
   tui,
+  utils::get_socket_path,
 };
 
+#[cfg(test)]
+mod harness;
+
+/// The screens of the app, which keybindings and styles are looked up by. The reader is always at the bottom of the
+/// screen stack, see `App::modes`.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Mode {
   #[default]
-  Home,
+  #[serde(alias = "Home")]
+  Reader,
+  /// Typing into the input box of the reader.
+  Insert,
+  Library,
+  Stats,
+  Settings,
+  Help,
+}
+
+impl FromStr for Mode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "Reader" | "Home" => Ok(Mode::Reader),
+      "Insert" => Ok(Mode::Insert),
+      "Library" => Ok(Mode::Library),
+      "Stats" => Ok(Mode::Stats),
+      "Settings" => Ok(Mode::Settings),
+      "Help" => Ok(Mode::Help),
+      _ => Err("expected one of: Reader, Insert, Library, Stats, Settings, Help".to_string()),
+    }
+  }
+}
+
+impl fmt::Display for Mode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{self:?}")
+  }
 }
 
 pub struct App {
+  pub args: Cli,
   pub config: Config,
   pub tick_rate: f64,
   pub frame_rate: f64,
   pub components: Vec<Box<dyn Component>>,
   pub should_quit: bool,
   pub should_suspend: bool,
-  pub mode: Mode,
+  /// Open screens from the bottom up, the one on top getting the input.
+  pub modes: Vec<Mode>,
+  /// The theme every component is styled with: the configured one, until the reader opens a book with a theme of its
+  /// own or another is picked with `CycleTheme` or `SetTheme`.
+  pub theme: String,
   pub last_tick_key_events: Vec<KeyEvent>,
+  pub pending_count: usize,
+  /// Words read and other events for remote control clients, see `remote::Remote`.
+  pub events: broadcast::Sender<Event>,
+  /// Where actions are written with `--record`.
+  pub recorder: Option<Recorder>,
+  /// The session read for `--replay`, until it starts.
+  pub replay: Option<Log>,
+  /// Whether actions are still being replayed from the `--replay` log.
+  pub replaying: bool,
 }
 
 impl App {
-  pub fn new(tick_rate: f64, frame_rate: f64) -> Result<Self> {
-    let home = Home::new();
-    let fps = FpsCounter::new();
-    let config = Config::new()?;
-    let mode = Mode::Home;
+  pub fn new(args: Cli) -> Result<Self> {
+    let (events, _) = broadcast::channel(256);
+    let replay = args.replay.as_deref().map(Log::read).transpose()?;
+    let file = args.file.clone().or_else(|| replay.as_ref().and_then(|log| log.header.file.clone()));
+    let home = Home::new().file(file).overrides(args.reading_overrides()).events(events.clone());
+    let mut components: Vec<Box<dyn Component>> = vec![
+      Box::new(home),
+      Box::new(Library::new()),
+      Box::new(Stats::new(events.subscribe())),
+      Box::new(Settings::new()),
+      Box::new(Help::new()),
+      Box::new(LogPane::new()),
+      Box::new(Notifications::new()),
+    ];
+    if args.fps {
+      components.push(Box::new(FpsCounter::new()));
+    }
+    let mut config = Config::new()?;
+    args.apply_overrides(&mut config);
     Ok(Self {
-      tick_rate,
-      frame_rate,
-      components: vec![Box::new(home), Box::new(fps)],
+      tick_rate: args.tick_rate,
+      frame_rate: args.frame_rate,
+      args,
+      components,
       should_quit: false,
       should_suspend: false,
+      theme: config.reading.theme.clone(),
       config,
-      mode,
+      modes: vec![Mode::Reader],
       last_tick_key_events: Vec::new(),
+      pending_count: 0,
+      events,
+      recorder: None,
+      replaying: replay.is_some(),
+      replay,
     })
   }
 
+  /// The screen on top of the stack.
+  pub fn mode(&self) -> Mode {
+    self.modes.last().copied().unwrap_or_default()
+  }
+
+  /// Opens `mode` on top of the current screen, or goes back to it when it is already open.
+  pub fn push_mode(&mut self, mode: Mode) {
+    match self.modes.iter().position(|open| *open == mode) {
+      Some(i) => self.modes.truncate(i + 1),
+      None => self.modes.push(mode),
+    }
+  }
+
+  /// Closes the screen on top, the reader staying open.
+  pub fn pop_mode(&mut self) {
+    if self.modes.len() > 1 {
+      self.modes.pop();
+    }
+  }
+
+  /// The components to draw, in order: those of each open screen from the bottom up, then those that belong to no
+  /// screen, such as the toasts, over everything.
+  fn draw_order(&self) -> Vec<usize> {
+    let mut order: Vec<(usize, usize)> = self
+      .components
+      .iter()
+      .enumerate()
+      .filter_map(|(i, component)| {
+        let modes = component.modes();
+        let layer =
+          if modes.is_empty() { Some(usize::MAX) } else { self.modes.iter().position(|mode| modes.contains(mode)) };
+        layer.map(|layer| (layer, i))
+      })
+      .collect();
+    order.sort();
+    order.into_iter().map(|(_, i)| i).collect()
+  }
+
+  fn handle_key_event(&mut self, key: KeyEvent, action_tx: &mpsc::UnboundedSender<Action>) -> Result<()> {
+    let Some(keymap) = self.config.keybindings.get(&self.mode()) else {
+      return Ok(());
+    };
+    let action = if let Some(action) = keymap.get(&vec![key]) {
+      action.clone()
+    } else if let Some(digit) = self.count_digit(key) {
+      self.pending_count = self.pending_count.saturating_mul(10).saturating_add(digit);
+      action_tx.send(Action::PendingCount(self.pending_count))?;
+      return Ok(());
+    } else {
+      // If the key was not handled as a single key action,
+      // then consider it for multi-key combinations.
+      self.last_tick_key_events.push(key);
+
+      // Check for multi-key combinations
+      match keymap.get(&self.last_tick_key_events) {
+        Some(action) => action.clone(),
+        None => {
+          if self.pending_count > 0 && key.code == KeyCode::Esc {
+            self.pending_count = 0;
+            action_tx.send(Action::PendingCount(0))?;
+          }
+          return Ok(());
+        },
+      }
+    };
+    let action = self.take_count(action, action_tx)?;
+    log::info!("Got action: {action:?}");
+    action_tx.send(action)?;
+    Ok(())
+  }
+
+  fn count_digit(&self, key: KeyEvent) -> Option<usize> {
+    match key.code {
+      KeyCode::Char(c)
+        if self.mode() == Mode::Reader && key.modifiers.is_empty() && (c != '0' || self.pending_count > 0) =>
+      {
+        c.to_digit(10).map(|d| d as usize)
+      },
+      _ => None,
+    }
+  }
+
+  fn take_count(&mut self, action: Action, action_tx: &mpsc::UnboundedSender<Action>) -> Result<Action> {
+    if self.pending_count == 0 {
+      return Ok(action);
+    }
+    let count = std::mem::take(&mut self.pending_count);
+    action_tx.send(Action::PendingCount(0))?;
+    Ok(action.with_count(count))
+  }
+
   pub async fn run(&mut self) -> Result<()> {
     let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+    if let Some(path) = &self.args.record {
+      let recorder = Recorder::create(path, self.args.file.clone());
+      self.recorder = Some(recorder.wrap_err_with(|| format!("Failed to record to {}", path.display()))?);
+    }
+    let (replay_tx, mut replay_rx) = mpsc::unbounded_channel();
+    let replay = self.replay.take().map(|log| log.replay(replay_tx));
 
     let mut tui = tui::Tui::new()?;
     tui.tick_rate(self.tick_rate);
     tui.frame_rate(self.frame_rate);
+    tui.always_render(self.args.always_render);
     tui.enter()?;
 
+    self.init(&action_tx)?;
+    let config_watcher = Self::watch_config(action_tx.clone());
+    #[cfg(unix)]
+    let _remote = self.start_remote(&action_tx);
+
+    loop {
+      // Actions sent from timers, such as the next word during playback, are handled as soon as they arrive rather
+      // than waiting for the next terminal event.
+      let (mut next_action, mut replayed) = (None, None);
+      let event = tokio::select! {
+        event = tui.next() => event,
+        action = action_rx.recv() => {
+          next_action = action;
+          None
+        },
+        action = replay_rx.recv(), if self.replaying => {
+          replayed = action;
+          if replayed.is_none() {
+            log::info!("Replay finished");
+            self.replaying = false;
+          }
+          None
+        },
+      };
+      if let Some(e) = event {
+        self.handle_event(e, &mut tui, &action_tx)?;
+      }
+      if let Some(action) = replayed {
+        self.handle_action(action, &mut tui, &action_tx)?;
+      }
+      while let Some(action) = next_action.take().or_else(|| action_rx.try_recv().ok()) {
+        self.handle_sent_action(action, &mut tui, &action_tx)?;
+      }
+      if self.should_suspend {
+        tui.suspend()?;
+        action_tx.send(Action::Resume)?;
+        tui = tui::Tui::new()?;
+        tui.tick_rate(self.tick_rate);
+        tui.frame_rate(self.frame_rate);
+        tui.always_render(self.args.always_render);
+        tui.enter()?;
+      } else if self.should_quit {
+        tui.stop()?;
+        break;
+      }
+    }
+    config_watcher.abort();
+    if let Some(replay) = replay {
+      replay.abort();
+    }
+    tui.exit()?;
+    Ok(())
+  }
+
+  /// Hands the action channel and config to the components and lets them start, e.g. loading the book.
+  pub fn init(&mut self, action_tx: &mpsc::UnboundedSender<Action>) -> Result<()> {
     for component in self.components.iter_mut() {
       component.register_action_handler(action_tx.clone())?;
     }
 
     for component in self.components.iter_mut() {
       component.register_config_handler(self.config.clone())?;
+      component.theme_changed(&self.theme);
     }
 
     for component in self.components.iter_mut() {
       component.init()?;
     }
+    Ok(())
+  }
+
+  /// Styles every component with `theme`, or warns about it when there is no such theme.
+  pub fn set_theme(
+    &mut self,
+    theme: &str,
+    tui: &mut tui::Tui,
+    action_tx: &mpsc::UnboundedSender<Action>,
+  ) -> Result<()> {
+    if let Err(e) = check_theme(theme) {
+      action_tx.send(Action::Warning(e))?;
+      return Ok(());
+    }
+    self.theme = theme.to_string();
+    for component in self.components.iter_mut() {
+      component.theme_changed(theme);
+    }
+    tui.request_render();
+    Ok(())
+  }
 
-    loop {
-      if let Some(e) = tui.next().await {
-        match e {
-          tui::Event::Quit => action_tx.send(Action::Quit)?,
-          tui::Event::Tick => action_tx.send(Action::Tick)?,
-          tui::Event::Render => action_tx.send(Action::Render)?,
-          tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
-          tui::Event::Key(key) => {
-            if let Some(keymap) = self.config.keybindings.get(&self.mode) {
-              if let Some(action) = keymap.get(&vec![key.clone()]) {
-                log::info!("Got action: {action:?}");
-                action_tx.send(action.clone())?;
-              } else {
-                // If the key was not handled as a single key action,
-                // then consider it for multi-key combinations.
-                self.last_tick_key_events.push(key);
-
-                // Check for multi-key combinations
-                if let Some(action) = keymap.get(&self.last_tick_key_events) {
-                  log::info!("Got action: {action:?}");
-                  action_tx.send(action.clone())?;
-                }
-              }
-            };
-          },
-          _ => {},
-        }
-        for component in self.components.iter_mut() {
-          if let Some(action) = component.handle_events(Some(e.clone()))? {
-            action_tx.send(action)?;
-          }
+  /// Turns a terminal event into actions and passes it on to the components, input only to those that have focus.
+  pub fn handle_event(
+    &mut self,
+    event: tui::Event,
+    tui: &mut tui::Tui,
+    action_tx: &mpsc::UnboundedSender<Action>,
+  ) -> Result<()> {
+    match event {
+      tui::Event::Init => tui.request_render(),
+      tui::Event::Quit => action_tx.send(Action::Quit)?,
+      tui::Event::Tick => action_tx.send(Action::Tick)?,
+      tui::Event::Render => action_tx.send(Action::Render)?,
+      tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
+      tui::Event::FocusLost => action_tx.send(Action::FocusLost)?,
+      tui::Event::FocusGained => action_tx.send(Action::FocusGained)?,
+      tui::Event::Key(key) => self.handle_key_event(key, action_tx)?,
+      _ => {},
+    }
+    let input = matches!(event, tui::Event::Key(_) | tui::Event::Mouse(_) | tui::Event::Paste(_));
+    let mode = self.mode();
+    for component in self.components.iter_mut() {
+      if input && !component.modes().contains(&mode) {
+        continue;
+      }
+      if let Some(action) = component.handle_events(Some(event.clone()))? {
+        action_tx.send(action)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Handles an action sent by a component or timer. While replaying, the log stands in for what the reader did and
+  /// everything that followed from it, so only actions a replay does not provide are handled.
+  pub fn handle_sent_action(
+    &mut self,
+    action: Action,
+    tui: &mut tui::Tui,
+    action_tx: &mpsc::UnboundedSender<Action>,
+  ) -> Result<()> {
+    if self.replaying && session::is_recorded(&action) {
+      return Ok(());
+    }
+    self.handle_action(action, tui, action_tx)
+  }
+
+  pub fn handle_action(
+    &mut self,
+    action: Action,
+    tui: &mut tui::Tui,
+    action_tx: &mpsc::UnboundedSender<Action>,
+  ) -> Result<()> {
+    // Actions sent for every word or batch of tokens would fill the log, so they only show at the trace level.
+    match action {
+      Action::Tick | Action::Render | Action::Redraw => {},
+      Action::SreadText(_) | Action::TextLoaded => log::trace!("{action:?}"),
+      _ => log::debug!("{action:?}"),
+    }
+    self.record(&action, action_tx)?;
+    let mode = self.mode();
+    match action {
+      Action::Tick => {
+        self.last_tick_key_events.drain(..);
+      },
+      Action::Quit => self.should_quit = true,
+      Action::ReloadConfig => self.reload_config(tui, action_tx)?,
+      Action::Suspend => self.should_suspend = true,
+      Action::Resume => self.should_suspend = false,
+      Action::EnterInsert => self.push_mode(Mode::Insert),
+      Action::EnterNormal if self.mode() == Mode::Insert => self.pop_mode(),
+      Action::PushMode(mode) => {
+        self.push_mode(mode);
+        tui.request_render();
+      },
+      Action::PopMode => {
+        self.pop_mode();
+        tui.request_render();
+      },
+      Action::CycleTheme => self.set_theme(next_theme(&self.theme), tui, action_tx)?,
+      Action::SetTheme(ref theme) => self.set_theme(theme, tui, action_tx)?,
+      Action::ToggleShowHelp => {
+        match self.mode() {
+          Mode::Help => self.pop_mode(),
+          _ => self.push_mode(Mode::Help),
         }
+        tui.request_render();
+      },
+      Action::Resize(w, h) => {
+        tui.resize(Rect::new(0, 0, w, h))?;
+        self.draw(tui, action_tx)?;
+      },
+      Action::Redraw => tui.request_render(),
+      Action::Render => self.draw(tui, action_tx)?,
+      _ => {},
+    }
+    if self.mode() != mode {
+      let mode = self.mode();
+      for component in self.components.iter_mut() {
+        component.mode_changed(mode);
       }
+    }
+    for component in self.components.iter_mut() {
+      if let Some(action) = component.update(action.clone())? {
+        action_tx.send(action)?
+      };
+    }
+    Ok(())
+  }
 
-      while let Ok(action) = action_rx.try_recv() {
-        if action != Action::Tick && action != Action::Render {
-          log::debug!("{action:?}");
+  /// Writes the action to the `--record` log. Reading goes on without recording when the log cannot be written.
+  fn record(&mut self, action: &Action, action_tx: &mpsc::UnboundedSender<Action>) -> Result<()> {
+    let Some(recorder) = &mut self.recorder else {
+      return Ok(());
+    };
+    if !session::is_recorded(action) {
+      return Ok(());
+    }
+    if let Err(e) = recorder.record(action) {
+      self.recorder = None;
+      action_tx.send(Action::Error(format!("Stopped recording: {e}")))?;
+    }
+    Ok(())
+  }
+
+  fn draw(&mut self, tui: &mut tui::Tui, action_tx: &mpsc::UnboundedSender<Action>) -> Result<()> {
+    let order = self.draw_order();
+    tui.draw(|f| {
+      for i in order {
+        let r = self.components[i].draw(f, f.size());
+        if let Err(e) = r {
+          action_tx.send(Action::Error(format!("Failed to draw: {:?}", e))).ok();
         }
-        match action {
-          Action::Tick => {
-            self.last_tick_key_events.drain(..);
-          },
-          Action::Quit => self.should_quit = true,
-          Action::Suspend => self.should_suspend = true,
-          Action::Resume => self.should_suspend = false,
-          Action::Resize(w, h) => {
-            tui.resize(Rect::new(0, 0, w, h))?;
-            tui.draw(|f| {
-              for component in self.components.iter_mut() {
-                let r = component.draw(f, f.size());
-                if let Err(e) = r {
-                  action_tx.send(Action::Error(format!("Failed to draw: {:?}", e))).unwrap();
-                }
-              }
-            })?;
-          },
-          Action::Render => {
-            tui.draw(|f| {
-              for component in self.components.iter_mut() {
-                let r = component.draw(f, f.size());
-                if let Err(e) = r {
-                  action_tx.send(Action::Error(format!("Failed to draw: {:?}", e))).unwrap();
-                }
-              }
-            })?;
-          },
-          _ => {},
+      }
+    })?;
+    Ok(())
+  }
+
+  /// Starts listening for remote control commands. Reading goes on without it when the socket cannot be opened.
+  #[cfg(unix)]
+  fn start_remote(&self, action_tx: &mpsc::UnboundedSender<Action>) -> Option<remote::Remote> {
+    let path = self.args.socket.clone().unwrap_or_else(get_socket_path);
+    match remote::Remote::start(path, action_tx.clone(), self.events.clone()) {
+      Ok(remote) => Some(remote),
+      Err(e) => {
+        action_tx.send(Action::Warning(format!("Remote control is not available: {e}"))).ok();
+        None
+      },
+    }
+  }
+
+  /// Polls the config directory and requests a reload whenever a config file changes.
+  fn watch_config(action_tx: mpsc::UnboundedSender<Action>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+      let mut modified = Config::modified();
+      let mut interval = tokio::time::interval(Duration::from_secs(1));
+      loop {
+        interval.tick().await;
+        let current = Config::modified();
+        if current != modified {
+          modified = current;
+          if action_tx.send(Action::ReloadConfig).is_err() {
+            break;
+          }
         }
+      }
+    })
+  }
+
+  fn reload_config(&mut self, tui: &mut tui::Tui, action_tx: &mpsc::UnboundedSender<Action>) -> Result<()> {
+    match Config::new() {
+      Ok(mut config) => {
+        self.args.apply_overrides(&mut config);
         for component in self.components.iter_mut() {
-          if let Some(action) = component.update(action.clone())? {
-            action_tx.send(action)?
-          };
+          component.register_config_handler(config.clone())?;
         }
-      }
-      if self.should_suspend {
-        tui.suspend()?;
-        action_tx.send(Action::Resume)?;
-        tui = tui::Tui::new()?;
-        tui.tick_rate(self.tick_rate);
-        tui.frame_rate(self.frame_rate);
-        tui.enter()?;
-      } else if self.should_quit {
-        tui.stop()?;
-        break;
-      }
+        self.config = config;
+        // Styles and settings shown on screen may have changed.
+        tui.request_render();
+        action_tx.send(Action::Info("Reloaded configuration".to_string()))?;
+      },
+      Err(e) => action_tx.send(Action::Error(format!("Config not reloaded: {e}")))?,
     }
-    tui.exit()?;
     Ok(())
   }
 }
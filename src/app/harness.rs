@@ -0,0 +1,306 @@
+//! Drives an `App` the way `App::run` does, but against a terminal in memory and with time standing still until a test
+//! moves it on, so that the screen after a scripted sequence of events can be compared with a snapshot.
+
+use std::time::Duration;
+
+use clap::Parser;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use super::App;
+use crate::{
+  action::Action,
+  cli::Cli,
+  config::parse_key_sequence,
+  tui::{Backend, Event, Tui},
+};
+
+/// Time moves on in steps of this size, so that timers fire in order and words are shown when they are due.
+const STEP: Duration = Duration::from_millis(10);
+
+pub struct Harness {
+  pub app: App,
+  pub tui: Tui,
+  pub action_tx: UnboundedSender<Action>,
+  pub action_rx: UnboundedReceiver<Action>,
+  /// Time passed since the last `Event::Tick`.
+  pub since_tick: Duration,
+}
+
+impl Harness {
+  /// An app started with the command line `args` on a terminal of `width` by `height` cells. Tests must run with
+  /// `#[tokio::test(start_paused = true)]`.
+  pub async fn new(args: &[&str], width: u16, height: u16) -> Self {
+    let args = Cli::try_parse_from(std::iter::once(env!("CARGO_PKG_NAME")).chain(args.iter().copied())).unwrap();
+    let (action_tx, action_rx) = mpsc::unbounded_channel();
+    let (app, tui) = (App::new(args).unwrap(), Tui::test(width, height).unwrap());
+    let mut harness = Self { app, tui, action_tx, action_rx, since_tick: Duration::ZERO };
+    harness.app.init(&harness.action_tx).unwrap();
+    harness.send(Event::Init).await;
+    harness
+  }
+
+  /// Handles `event` and everything that follows from it without time passing.
+  pub async fn send(&mut self, event: Event) {
+    self.app.handle_event(event, &mut self.tui, &self.action_tx).unwrap();
+    self.settle().await;
+  }
+
+  /// Presses the keys of a sequence such as `<j><j><Shift-k>` one after another.
+  pub async fn keys(&mut self, keys: &str) {
+    for key in parse_key_sequence(keys).unwrap() {
+      self.send(Event::Key(key)).await;
+    }
+  }
+
+  pub async fn paste(&mut self, text: &str) {
+    self.send(Event::Paste(text.to_string())).await;
+  }
+
+  /// Lets `duration` pass, handling the actions of the timers that fire on the way and ticking at the app's rate.
+  pub async fn advance(&mut self, duration: Duration) {
+    let tick = Duration::from_secs_f64(1.0 / self.app.tick_rate);
+    let mut remaining = duration;
+    while !remaining.is_zero() {
+      let step = remaining.min(STEP);
+      tokio::time::advance(step).await;
+      remaining -= step;
+      self.since_tick += step;
+      if self.since_tick >= tick {
+        self.since_tick -= tick;
+        self.send(Event::Tick).await;
+      } else {
+        self.settle().await;
+      }
+    }
+  }
+
+  /// Handles actions until no task has any more to send and no component is busy, e.g. loading a text.
+  async fn settle(&mut self) {
+    loop {
+      // Spawned tasks, e.g. those of `Home::schedule_increment_text`, only run while this one yields.
+      for _ in 0..8 {
+        tokio::task::yield_now().await;
+      }
+      let mut idle = true;
+      while let Ok(action) = self.action_rx.try_recv() {
+        idle = false;
+        self.app.handle_sent_action(action, &mut self.tui, &self.action_tx).unwrap();
+      }
+      if idle && !self.app.components.iter().any(|component| component.is_busy()) {
+        break;
+      }
+      if idle {
+        // Texts are tokenized on a blocking thread, which the paused clock does not wait for.
+        std::thread::sleep(Duration::from_millis(1));
+      }
+    }
+  }
+
+  /// Draws a frame and returns it as text, one line per row with trailing spaces removed.
+  pub async fn screen(&mut self) -> String {
+    self.app.handle_action(Action::Render, &mut self.tui, &self.action_tx).unwrap();
+    self.settle().await;
+    let Backend::Test(backend) = self.tui.backend() else {
+      unreachable!("`Tui::test` draws to a `TestBackend`");
+    };
+    let buffer = backend.buffer();
+    let area = buffer.area;
+    let rows = (area.top()..area.bottom())
+      .map(|y| (area.left()..area.right()).map(|x| buffer.get(x, y).symbol.as_str()).collect::<String>());
+    rows.map(|row| row.trim_end().to_string()).collect::<Vec<_>>().join("\n")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use insta::assert_snapshot;
+
+  use super::*;
+  use crate::app::Mode;
+
+  const TEXT: &str = "Alice was beginning to get very tired of sitting by her sister on the bank, and of having \
+                      nothing to do.\n\nSo she was considering in her own mind whether the pleasure of making a \
+                      daisy-chain would be worth the trouble.\n\nThere was nothing so very remarkable in that.\n";
+
+  async fn pasted() -> Harness {
+    let mut harness = Harness::new(&[], 80, 24).await;
+    harness.paste(TEXT).await;
+    harness
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_load() {
+    let mut harness = Harness::new(&[], 80, 24).await;
+    assert_snapshot!("empty", harness.screen().await);
+    harness.paste(TEXT).await;
+    assert_snapshot!("loaded", harness.screen().await);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_play_pause() {
+    let mut harness = pasted().await;
+    harness.keys("< >").await;
+    harness.advance(Duration::from_secs(2)).await;
+    harness.keys("< >").await;
+    harness.advance(Duration::from_secs(1)).await;
+    let paused = harness.screen().await;
+    assert_snapshot!("paused", paused);
+    // Nothing moves on while paused.
+    harness.advance(Duration::from_secs(2)).await;
+    assert_eq!(harness.screen().await, paused);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_navigation() {
+    let mut harness = pasted().await;
+    harness.keys("<j><j><j><k>").await;
+    assert_snapshot!("words", harness.screen().await);
+    harness.keys("<}>").await;
+    assert_snapshot!("paragraph", harness.screen().await);
+    harness.keys("<2><j>").await;
+    assert_snapshot!("count", harness.screen().await);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_help() {
+    let mut harness = pasted().await;
+    harness.keys("<?>").await;
+    assert_snapshot!("help", harness.screen().await);
+    harness.keys("<?>").await;
+    // The keys pressed are shown until the next tick.
+    harness.advance(Duration::from_secs(1)).await;
+    assert_snapshot!("loaded", harness.screen().await);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_insert() {
+    let mut harness = pasted().await;
+    harness.keys("</>").await;
+    harness.keys("<r><a><b><b><i><t>").await;
+    assert_snapshot!("insert", harness.screen().await);
+    harness.keys("<enter>").await;
+    assert_snapshot!("inserted", harness.screen().await);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_notifications() {
+    let mut harness = pasted().await;
+    // Pasted text has no book to keep settings with.
+    harness.keys("<s>").await;
+    assert_snapshot!("toast", harness.screen().await);
+    harness.advance(Duration::from_secs(6)).await;
+    assert_snapshot!("loaded", harness.screen().await);
+    harness.keys("<e>").await;
+    assert_snapshot!("error_log", harness.screen().await);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_screens() {
+    let mut harness = pasted().await;
+    harness.keys("<j><j>").await;
+    harness.advance(Duration::from_secs(1)).await;
+    let reader = harness.screen().await;
+    harness.keys("<o>").await;
+    assert_eq!(harness.app.modes, [Mode::Reader, Mode::Library]);
+    harness.keys("<j><j><k>").await;
+    assert_snapshot!("library", harness.screen().await);
+    // Keys went to the library only.
+    harness.keys("<esc>").await;
+    harness.advance(Duration::from_secs(1)).await;
+    assert_eq!(harness.screen().await, reader);
+
+    harness.keys("< >").await;
+    harness.advance(Duration::from_secs(2)).await;
+    harness.keys("< ><i>").await;
+    assert_snapshot!("stats", harness.screen().await);
+    // Help opens over any screen, and opening a screen that is already open goes back to it.
+    harness.keys("<?>").await;
+    assert_eq!(harness.app.modes, [Mode::Reader, Mode::Stats, Mode::Help]);
+    harness.app.handle_action(Action::PushMode(Mode::Stats), &mut harness.tui, &harness.action_tx).unwrap();
+    assert_eq!(harness.app.modes, [Mode::Reader, Mode::Stats]);
+    harness.keys("<q>").await;
+    assert_eq!(harness.app.modes, [Mode::Reader]);
+
+    harness.keys("<,>").await;
+    assert_snapshot!("settings", harness.screen().await);
+    harness.keys("<q><q>").await;
+    // The reader stays open, and its `q` quits.
+    assert_eq!(harness.app.modes, [Mode::Reader]);
+    assert!(harness.app.should_quit);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_library_open() {
+    let mut harness = Harness::new(&[], 160, 24).await;
+    harness.keys("<o><j><enter>").await;
+    assert_eq!(harness.app.modes, [Mode::Reader]);
+    let screen = harness.screen().await;
+    assert!(screen.lines().next().unwrap().contains("fScottFitzerald_ofTheBeautifulAndTheDamned.txt"), "{screen}");
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_reload_renders() {
+    let mut harness = pasted().await;
+    let requested = harness.tui.render_requested.clone();
+    let rendered = || async { tokio::time::timeout(Duration::ZERO, requested.notified()).await.is_ok() };
+    rendered().await;
+    assert!(!rendered().await);
+    harness.app.handle_action(Action::ReloadConfig, &mut harness.tui, &harness.action_tx).unwrap();
+    assert!(rendered().await);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_replay_notifications() {
+    let mut harness = pasted().await;
+    harness.app.replaying = true;
+    // The log stands in for reading, but failures while replaying are still shown.
+    harness.action_tx.send(Action::IncrementText(1)).unwrap();
+    harness.action_tx.send(Action::Error("Failed to read book.txt".into())).unwrap();
+    harness.settle().await;
+    let screen = harness.screen().await;
+    assert!(screen.contains("Failed to read book.txt"), "{screen}");
+    assert!(screen.contains("Current Word: 0/"), "{screen}");
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_insert_follows_screen() {
+    let mut harness = pasted().await;
+    harness.keys("</><a><b>").await;
+    // Closing the input other than with ESC, here from a replay, leaves the reader taking keys again.
+    harness.app.handle_action(Action::PopMode, &mut harness.tui, &harness.action_tx).unwrap();
+    harness.keys("<j>").await;
+    let screen = harness.screen().await;
+    assert!(screen.contains("│ab ") && screen.contains("Current Word: 1/"), "{screen}");
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_set_theme() {
+    let mut harness = pasted().await;
+    harness.keys("<t>").await;
+    assert_eq!(harness.app.theme, "light");
+    harness.action_tx.send(Action::SetTheme("solarized".into())).unwrap();
+    harness.action_tx.send(Action::SetTheme("solarised".into())).unwrap();
+    harness.settle().await;
+    assert_eq!(harness.app.theme, "solarized");
+    let screen = harness.screen().await;
+    assert!(screen.contains("Unknown theme `solarised`"), "{screen}");
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_help_follows_theme() {
+    let mut harness = pasted().await;
+    // Colour of the top left corner of the help's border.
+    let border = |harness: &Harness| match harness.tui.backend() {
+      Backend::Test(backend) => backend.buffer().get(4, 2).fg,
+      _ => unreachable!("`Tui::test` draws to a `TestBackend`"),
+    };
+    harness.keys("<?>").await;
+    harness.screen().await;
+    let dark = border(&harness);
+    harness.keys("<?><t><?>").await;
+    harness.screen().await;
+    assert_eq!(harness.app.theme, "light");
+    assert_eq!(Some(border(&harness)), harness.app.config.style(Mode::Help, "light", "help").fg);
+    assert_ne!(border(&harness), dark);
+  }
+}
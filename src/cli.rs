@@ -1,8 +1,16 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
-use crate::utils::version;
+use color_eyre::eyre::Result;
+
+use crate::{
+  config::{
+    check::{check_file, Severity},
+    Config, DumpFormat, ReadingOverrides,
+  },
+  utils::version,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
@@ -14,8 +22,131 @@ pub struct Cli {
     short,
     long,
     value_name = "FLOAT",
-    help = "Frame rate, i.e. number of frames per second",
+    help = "Frame rate, i.e. the most frames per second, rendered only when something changed",
     default_value_t = 60.0
   )]
   pub frame_rate: f64,
+
+  #[arg(long, help = "Show the number of ticks and frames rendered per second")]
+  pub fps: bool,
+
+  #[arg(long, help = "Render every frame even when nothing changed, to compare with `--fps`")]
+  pub always_render: bool,
+
+  #[arg(value_name = "FILE", help = "Text file to read, either a path or a book name from the library")]
+  pub file: Option<PathBuf>,
+
+  #[arg(short, long, value_name = "INT", help = "Words per minute, overrides `reading.wpm`")]
+  pub wpm: Option<u32>,
+
+  #[arg(short, long, value_name = "INT", help = "Words shown at a time, overrides `reading.chunk_size`")]
+  pub chunk_size: Option<usize>,
+
+  #[arg(long, value_name = "NAME", help = "Theme, overrides `reading.theme`")]
+  pub theme: Option<String>,
+
+  #[arg(long, help = "Disable optimal recognition point highlighting, overrides `reading.orp`")]
+  pub no_orp: bool,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    help = "Socket to listen on for remote control, defaults to one in the runtime directory"
+  )]
+  pub socket: Option<PathBuf>,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    conflicts_with_all = ["replay", "pipe"],
+    help = "Record every action to a JSON-lines log, to be replayed with `--replay`"
+  )]
+  pub record: Option<PathBuf>,
+
+  #[arg(
+    long,
+    value_name = "PATH",
+    conflicts_with = "pipe",
+    help = "Replay a log recorded with `--record`, reading the book it was recorded with unless FILE is given"
+  )]
+  pub replay: Option<PathBuf>,
+
+  #[arg(long, requires = "file", help = "Write the words to stdout instead of showing them, taking commands on stdin")]
+  pub pipe: bool,
+
+  #[arg(long, requires = "pipe", help = "Highlight the pivot letter with ANSI colours")]
+  pub pivot: bool,
+
+  #[arg(long, requires = "pipe", help = "Overwrite each word with the next instead of writing one per line")]
+  pub overwrite: bool,
+
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  #[command(subcommand, about = "Inspect the configuration")]
+  Config(ConfigCommand),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+  #[command(about = "Validate the config file, reporting problems with their line numbers")]
+  Check {
+    #[arg(value_name = "FILE", help = "Config file to check, defaults to the files in the config directory")]
+    file: Option<PathBuf>,
+  },
+  #[command(about = "Print the effective config, merged from the defaults, the config file and command line flags")]
+  Dump {
+    #[arg(short, long, value_enum, default_value_t = DumpFormat::Json5, help = "Output format")]
+    format: DumpFormat,
+  },
+}
+
+impl Cli {
+  /// Applies command line flags on top of the values read from the config file.
+  pub fn apply_overrides(&self, config: &mut Config) {
+    self.reading_overrides().apply(&mut config.reading);
+    if self.no_orp {
+      config.reading.orp = false;
+    }
+  }
+
+  /// The flags a book's own settings could override, to be applied on top of them again, see `Home::overrides`.
+  pub fn reading_overrides(&self) -> ReadingOverrides {
+    ReadingOverrides { wpm: self.wpm, chunk_size: self.chunk_size, theme: self.theme.clone(), ..Default::default() }
+  }
+}
+
+impl ConfigCommand {
+  /// Runs the subcommand, printing to stdout. Returns whether the config is free of errors.
+  pub fn run(&self, cli: &Cli) -> Result<bool> {
+    match self {
+      ConfigCommand::Check { file } => {
+        let files = file.clone().map_or_else(Config::files, |file| vec![file]);
+        if files.is_empty() {
+          println!("No config file found in {}", crate::utils::get_config_dir().display());
+        }
+        let mut errors = 0;
+        for file in files {
+          let diagnostics = check_file(&file)?;
+          errors += diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+          for diagnostic in &diagnostics {
+            println!("{}:{diagnostic}", file.display());
+          }
+          if diagnostics.is_empty() {
+            println!("{}: ok", file.display());
+          }
+        }
+        Ok(errors == 0)
+      },
+      ConfigCommand::Dump { format } => {
+        let mut config = Config::new()?;
+        cli.apply_overrides(&mut config);
+        print!("{}", config.dump(*format)?);
+        Ok(true)
+      },
+    }
+  }
 }
@@ -5,14 +5,21 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
   action::Action,
+  app::Mode,
   config::Config,
   tui::{Event, Frame},
 };
 
 pub mod home;
 pub mod fps;
+pub mod help;
+pub mod library;
+pub mod logs;
+pub mod notifications;
+pub mod settings;
+pub mod stats;
 
-//// ANCHOR: component
+// ANCHOR: component
 pub trait Component {
   #[allow(unused_variables)]
   fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
@@ -25,10 +32,28 @@ pub trait Component {
   fn init(&mut self) -> Result<()> {
     Ok(())
   }
+  /// The screens the component belongs to. It is drawn while one of them is open and gets key, mouse and paste
+  /// events while one of them is on top. Components of no screen, such as the toasts, are drawn over everything and
+  /// get no input.
+  fn modes(&self) -> &'static [Mode] {
+    &[]
+  }
+  /// Called with the screen on top whenever it changes, for components that act on it, such as the reader taking
+  /// text while `Mode::Insert` is.
+  #[allow(unused_variables)]
+  fn mode_changed(&mut self, mode: Mode) {}
+  /// Called with the theme to style with whenever it changes, see `App::theme`.
+  #[allow(unused_variables)]
+  fn theme_changed(&mut self, theme: &str) {}
+  /// Whether work the component started is still going on, for waiting on it in tests.
+  fn is_busy(&self) -> bool {
+    false
+  }
   fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
     let r = match event {
       Some(Event::Key(key_event)) => self.handle_key_events(key_event)?,
       Some(Event::Mouse(mouse_event)) => self.handle_mouse_events(mouse_event)?,
+      Some(Event::Paste(text)) => self.handle_paste_events(text)?,
       _ => None,
     };
     Ok(r)
@@ -42,9 +67,13 @@ pub trait Component {
     Ok(None)
   }
   #[allow(unused_variables)]
+  fn handle_paste_events(&mut self, text: String) -> Result<Option<Action>> {
+    Ok(None)
+  }
+  #[allow(unused_variables)]
   fn update(&mut self, action: Action) -> Result<Option<Action>> {
     Ok(None)
   }
   fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()>;
 }
-//// ANCHOR_END: component
+// ANCHOR_END: component
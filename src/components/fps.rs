@@ -41,27 +41,27 @@ impl FpsCounter {
     }
   }
 
-  fn app_tick(&mut self) -> Result<()> {
+  /// Counts a tick and updates both rates once a second. Returns whether they changed.
+  fn app_tick(&mut self) -> Result<bool> {
     self.app_frames += 1;
     let now = Instant::now();
     let elapsed = (now - self.app_start_time).as_secs_f64();
-    if elapsed >= 1.0 {
-      self.app_fps = self.app_frames as f64 / elapsed;
-      self.app_start_time = now;
-      self.app_frames = 0;
+    if elapsed < 1.0 {
+      return Ok(false);
     }
-    Ok(())
+    self.app_fps = self.app_frames as f64 / elapsed;
+    self.app_start_time = now;
+    self.app_frames = 0;
+    self.render_tick(now)?;
+    Ok(true)
   }
 
-  fn render_tick(&mut self) -> Result<()> {
-    self.render_frames += 1;
-    let now = Instant::now();
+  /// Frames are only rendered when something changed, so the render rate is updated on ticks rather than frames.
+  fn render_tick(&mut self, now: Instant) -> Result<()> {
     let elapsed = (now - self.render_start_time).as_secs_f64();
-    if elapsed >= 1.0 {
-      self.render_fps = self.render_frames as f64 / elapsed;
-      self.render_start_time = now;
-      self.render_frames = 0;
-    }
+    self.render_fps = self.render_frames as f64 / elapsed;
+    self.render_start_time = now;
+    self.render_frames = 0;
     Ok(())
   }
 }
@@ -69,15 +69,15 @@ impl FpsCounter {
 impl Component for FpsCounter {
   fn update(&mut self, action: Action) -> Result<Option<Action>> {
     if let Action::Tick = action {
-      self.app_tick()?
-    };
-    if let Action::Render = action {
-      self.render_tick()?
+      if self.app_tick()? {
+        return Ok(Some(Action::Redraw));
+      }
     };
     Ok(None)
   }
 
   fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    self.render_frames += 1;
     let rects = Layout::default()
       .direction(Direction::Vertical)
       .constraints(vec![
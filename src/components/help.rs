@@ -0,0 +1,97 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+use super::{Component, Frame};
+use crate::{action::Action, app::Mode, config::Config};
+
+/// Keys of the reader and what they do, in the order they are listed.
+const ROWS: &[(&str, &str)] = &[
+  ("?", "Open Help"),
+  ("l", "Load Text"),
+  ("Paste", "Read Pasted Text"),
+  ("Space", "Play/Pause Text"),
+  ("j", "Increment Text"),
+  ("k", "Decrement Text"),
+  ("J", "Forward 10 Words"),
+  ("K", "Back 10 Words"),
+  ("}", "Next Paragraph"),
+  ("{", "Previous Paragraph"),
+  ("1-9", "Count Prefix, e.g. 25j or 3}"),
+  ("t", "Cycle Theme"),
+  ("b", "Toggle Big Font"),
+  ("+/-", "Faster/Slower (saved for the book)"),
+  ("s", "Edit Book Settings"),
+  ("S", "Reset Book Settings"),
+  ("p", "Toggle Poetry Mode"),
+  ("c", "Toggle Context"),
+  ("e", "Show Warnings and Errors"),
+  ("L", "Toggle Log Pane"),
+  ("o", "Open Library"),
+  ("i", "Show Reading Stats"),
+  (",", "Show Settings"),
+  ("", ""),
+  ("/", "Enter Input"),
+  ("ESC", "Exit Input"),
+  ("Enter", "Submit Input"),
+  ("q", "Quit"),
+];
+
+/// The key bindings, shown over the screen below while `Mode::Help` is open. `j` and `k` scroll when they do not fit.
+#[derive(Default)]
+pub struct Help {
+  pub config: Config,
+  pub theme: String,
+  pub scroll: usize,
+}
+
+impl Help {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn style(&self, name: &str) -> Style {
+    self.config.style(Mode::Help, &self.theme, name)
+  }
+}
+
+impl Component for Help {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.config = config;
+    Ok(())
+  }
+
+  fn theme_changed(&mut self, theme: &str) {
+    self.theme = theme.to_string();
+  }
+
+  fn modes(&self) -> &'static [Mode] {
+    &[Mode::Help]
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    match key.code {
+      KeyCode::Char('j') | KeyCode::Down => self.scroll = (self.scroll + 1).min(ROWS.len() - 1),
+      KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+      _ => return Ok(None),
+    }
+    Ok(Some(Action::Redraw))
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    let rect = rect.inner(&Margin { horizontal: 4, vertical: 2 });
+    f.render_widget(Clear, rect);
+    let block = Block::default()
+      .title(Line::from(vec![Span::styled("Key Bindings", Style::default().add_modifier(Modifier::BOLD))]))
+      .borders(Borders::ALL)
+      .border_style(self.style("help"));
+    f.render_widget(block, rect);
+    let rows = ROWS.iter().skip(self.scroll).map(|(key, action)| Row::new(vec![*key, *action]));
+    let table = Table::new(rows.collect::<Vec<_>>())
+      .header(Row::new(vec!["Key", "Action"]).bottom_margin(1).style(Style::default().add_modifier(Modifier::BOLD)))
+      .widths(&[Constraint::Percentage(10), Constraint::Percentage(90)])
+      .column_spacing(1);
+    f.render_widget(table, rect.inner(&Margin { vertical: 4, horizontal: 2 }));
+    Ok(())
+  }
+}
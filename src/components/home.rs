@@ -4,45 +4,117 @@ use futures::future::{abortable, Abortable};
 use log::error;
 use ratatui::{prelude::*, widgets::*};
 use std::future::Future;
-use std::{collections::HashMap, fs, thread, time::Duration};
+use std::{
+  collections::HashMap,
+  fs, thread,
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::Duration,
+};
 
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{
+  broadcast,
+  mpsc::{self, error::TryRecvError, UnboundedReceiver, UnboundedSender},
+};
 use tokio::task::JoinHandle;
 use tokio::time::sleep;
 
 use tracing::trace;
-use tui_input::{backend::crossterm::EventHandler, Input};
+use tui_input::{backend::crossterm::EventHandler, Input, InputRequest};
+use unicode_width::UnicodeWidthStr;
 
 use super::{Component, Frame};
-use crate::{action::Action, config::key_event_to_string};
+use crate::{
+  action::Action,
+  app::Mode as AppMode,
+  config::{key_event_to_string, Config, PoetryMode, ReadingConfig, ReadingOverrides},
+  font::{self, glyph_width, Font},
+  hyphenation::{self, Hyphenator},
+  playback::Schedule,
+  remote::Event,
+  state::ReadingState,
+  text::{self, is_break, Buffer, SourceLine, Text, TokenKind, Tokens, LINE_BREAK, STANZA_BREAK},
+};
 
-#[derive(Default, Copy, Clone, PartialEq, Eq)]
+const DEFAULT_BOOK: &str = "./assets/lewisCarroll_alicesAdventuresInWonderland.txt";
+/// Frames of the spinner shown while `is_busy`, one per tick.
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Mode {
   #[default]
   Normal,
   Insert,
-  Processing,
+}
+
+/// A position to move to once the text has been loaded far enough.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Seek {
+  Token(usize),
+  /// The nth word, not counting break markers, which depend on poetry mode.
+  Word(usize),
 }
 
 #[derive(Default)]
 pub struct Home {
-  pub show_help: bool,
   pub counter: usize,
   pub app_ticker: usize,
   pub render_ticker: usize,
-  pub mode: Mode,
+  /// The screen on top, which decides whether keys are typed into the input, see `mode`.
+  pub app_mode: AppMode,
+  /// Loads under way, counted up by `EnterProcessing` and down by `ExitProcessing`.
+  pub processing: usize,
   pub input: Input,
   pub action_tx: Option<UnboundedSender<Action>>,
   pub keymap: HashMap<KeyEvent, Action>,
   pub text: Vec<String>,
   pub last_events: Vec<KeyEvent>,
+  pub pending_count: usize,
+  pub editing_settings: bool,
 
-  pub text_array: Vec<String>,
-  pub text_current_word: String,
+  pub text_path: Option<PathBuf>,
+  pub text_key: Option<String>,
+  pub text_source: String,
+  pub text_looks_like_verse: bool,
+  pub text_verse: bool,
+  pub text_array: Text,
+  pub text_paragraphs: Vec<usize>,
+  /// Index of the first token of every chapter heading, see `text::is_chapter`.
+  pub text_chapters: Vec<usize>,
+  /// The chapter last reported in an `Event::Chapter`.
+  pub text_chapter: Option<usize>,
+  pub text_lines: Vec<SourceLine>,
+  /// The words on screen when a chunk has more than one, see `current_word`.
+  pub text_chunk: String,
   pub text_current_index: usize,
+  /// Pieces of the current word when it is too long to show at once, see `hyphenation::split_word`.
+  pub text_pieces: Vec<String>,
+  pub text_piece: usize,
   pub text_length: usize,
+  /// Batches of tokens from the background task started by `text_tokenize`, until it finishes.
+  pub text_batches: Option<UnboundedReceiver<Tokens>>,
+  pub text_seek: Option<Seek>,
   pub text_play_on: bool,
+  pub text_play_handle: Option<JoinHandle<()>>,
+  pub text_schedule: Option<Schedule>,
   pub text_read_rate: u32,
+
+  pub config: Config,
+  pub reading: ReadingConfig,
+  pub hyphenator: Option<Box<dyn Hyphenator>>,
+  pub state: ReadingState,
+  /// Settings given on the command line, which take precedence over the book's own.
+  pub cli_overrides: ReadingOverrides,
+  /// The theme to style with, see `App::theme`.
+  pub theme: String,
+  pub big_font: bool,
+  pub show_context: bool,
+  pub font: Font,
+  pub auto_paused: bool,
+  pub resume_countdown: Option<u64>,
+  pub resume_handle: Option<JoinHandle<()>>,
+  /// Where the words read are reported to remote control clients.
+  pub events: Option<broadcast::Sender<Event>>,
 }
 
 impl Home {
@@ -55,17 +127,38 @@ impl Home {
     self
   }
 
+  pub fn file(mut self, file: Option<PathBuf>) -> Self {
+    self.text_path = file;
+    self
+  }
+
+  pub fn overrides(mut self, overrides: ReadingOverrides) -> Self {
+    self.cli_overrides = overrides;
+    self
+  }
+
+  pub fn events(mut self, events: broadcast::Sender<Event>) -> Self {
+    self.events = Some(events);
+    self
+  }
+
   pub fn tick(&mut self) {
-    log::info!("Tick");
     self.app_ticker = self.app_ticker.saturating_add(1);
     self.last_events.drain(..);
   }
 
   pub fn render_tick(&mut self) {
-    log::debug!("Render Tick");
     self.render_ticker = self.render_ticker.saturating_add(1);
   }
 
+  /// Whether keys are typed into the input, which follows the app's `Mode::Insert`. Loading leaves it as it is.
+  pub fn mode(&self) -> Mode {
+    match self.app_mode {
+      AppMode::Insert => Mode::Insert,
+      _ => Mode::Normal,
+    }
+  }
+
   pub fn add(&mut self, s: String) {
     self.text.push(s)
   }
@@ -100,62 +193,692 @@ impl Home {
     });
   }
 
-  pub fn schedule_sread_text(&mut self, i: usize) {
-    self.text_play_on = !self.text_play_on;
+  pub fn schedule_sread_text(&mut self) {
+    if self.resume_handle.is_some() {
+      self.cancel_resume_countdown();
+      self.auto_paused = false;
+    } else if self.text_play_on {
+      self.pause_text();
+    } else {
+      self.countdown_to_play(self.reading.start_countdown);
+    }
+  }
 
-    let mut handles = Vec::new();
-    let j = self.text_length - self.text_current_index;
-    let tx = self.action_tx.clone().unwrap();
+  /// Starts playback straight away, unlike `schedule_sread_text` which counts down first.
+  pub fn play(&mut self) {
+    self.cancel_resume_countdown();
+    self.auto_paused = false;
+    self.play_text();
+  }
 
-    let text_current_index = self.text_current_index;
-    let text_length = self.text_length;
+  pub fn pause(&mut self) {
+    self.cancel_resume_countdown();
+    self.auto_paused = false;
+    self.pause_text();
+  }
+
+  pub fn play_text(&mut self) {
+    if self.text_play_on || self.text_length == 0 {
+      return;
+    }
+    self.text_play_on = true;
+    self.auto_paused = false;
+    self.text_schedule = Some(Schedule::start());
+    self.schedule_next_word();
+  }
+
+  /// Wakes up when the words on screen are due to be replaced, see `Schedule`.
+  pub fn schedule_next_word(&mut self) {
+    let delay = self.word_delay();
+    let step = self.chunk_end(self.text_current_index) - self.text_current_index;
+    let due = self.text_schedule.get_or_insert_with(Schedule::start).next(delay);
+    let tx = self.action_tx.clone().unwrap();
+    self.text_play_handle = Some(tokio::spawn(async move {
+      tokio::time::sleep_until(due).await;
+      tx.send(Action::SreadText(step)).unwrap();
+    }));
+  }
 
+  pub fn pause_text(&mut self) {
+    if let Some(handle) = self.text_play_handle.take() {
+      handle.abort();
+    }
     if self.text_play_on {
-      handles.push(tokio::spawn(async move {
-        while text_current_index < text_length {
-          tokio::time::sleep(Duration::from_secs(1) / 2).await;
-          tx.send(Action::EnterProcessing).unwrap();
-          tx.send(Action::SreadText(i)).unwrap();
-          tx.send(Action::ExitProcessing).unwrap();
-        }
-      }));
+      self.text_play_on = false;
+      self.save_position();
+    }
+    if let Some(schedule) = self.text_schedule.take() {
+      log::info!("Read at {:.0} wpm, target {:.0} wpm", schedule.measured_wpm(), schedule.target_wpm());
+    }
+  }
+
+  pub fn chunk_size(&self) -> usize {
+    self.reading.chunk_size.max(1)
+  }
+
+  /// How long the current chunk stays on screen, including punctuation and paragraph pauses.
+  pub fn word_delay(&self) -> Duration {
+    let end = self.chunk_end(self.text_current_index);
+    let base = 60.0 / self.text_read_rate.max(1) as f64 * (end - self.text_current_index) as f64;
+    let paragraph_end = end == self.text_length || self.text_paragraphs.binary_search(&end).is_ok();
+    let last = end.saturating_sub(1);
+    if self.text_pieces.len() > 1 {
+      let last_piece = self.text_piece + 1 == self.text_pieces.len();
+      let multiplier = pause_multiplier(self.current_word(), paragraph_end && last_piece, &self.reading);
+      return Duration::from_secs_f64(base * multiplier);
+    }
+    let multiplier = (self.text_current_index..end)
+      .map(|i| pause_multiplier(&self.text_array[i], paragraph_end && i == last, &self.reading))
+      .fold(1.0, f64::max);
+    Duration::from_secs_f64(base * multiplier)
+  }
+
+  // UI
+  pub fn style(&self, name: &str) -> Style {
+    self.config.style(AppMode::Reader, &self.theme, name)
+  }
+
+  /// The current word, padded so that its pivot letter sits on the centre column.
+  pub fn word_line(&self) -> Line<'static> {
+    let word = self.current_word();
+    if word.is_empty() {
+      return Line::default();
+    }
+    if is_break(word) {
+      let marker = if word == LINE_BREAK { "↵" } else { "¶" };
+      return Line::from(Span::styled(marker, self.style("dim")));
+    }
+    let graphemes = text::visual_order(word);
+    let join = |graphemes: &[(usize, &str)]| graphemes.iter().map(|(_, g)| *g).collect::<String>();
+    if !self.reading.orp {
+      return Line::from(Span::styled(join(&graphemes), self.style("word")));
+    }
+    let pivot = visual_pivot(&graphemes);
+    let width = |graphemes: &[(usize, &str)]| graphemes.iter().map(|(_, g)| g.width()).sum::<usize>();
+    let (before, after) = (width(&graphemes[..pivot]), width(&graphemes[pivot + 1..]));
+    Line::from(vec![
+      Span::raw(" ".repeat(after.saturating_sub(before))),
+      Span::styled(join(&graphemes[..pivot]), self.style("word")),
+      Span::styled(graphemes[pivot].1.to_string(), self.style("pivot")),
+      Span::styled(join(&graphemes[pivot + 1..]), self.style("word")),
+      Span::raw(" ".repeat(before.saturating_sub(after))),
+    ])
+  }
+
+  /// The text around the current word: verse in its original lines, prose as the surrounding words.
+  pub fn context_lines(&self, rows: usize) -> Vec<Line<'static>> {
+    let index = self.text_current_index;
+    if index >= self.text_length || rows == 0 {
+      return Vec::new();
+    }
+    let current = index..self.chunk_end(index);
+    let span = |i: usize| {
+      let style = if current.contains(&i) { self.style("highlight") } else { self.style("dim") };
+      let word = text::visual_order(&self.text_array[i]).into_iter().map(|(_, g)| g).collect::<String>();
+      let separator = self.text_array.get(i + 1).map_or(" ", |next| text::separator(&self.text_array[i], next));
+      Span::styled(word + separator, style)
+    };
+    // Words are laid out left to right, so a right-to-left line has to be reversed as a whole.
+    let ordered = |mut spans: Vec<Span<'static>>| {
+      if spans.iter().find(|s| !s.content.trim().is_empty()).is_some_and(|s| text::is_rtl(&s.content)) {
+        spans.reverse();
+      }
+      Line::from(spans)
+    };
+    if self.text_verse {
+      let line = self.text_lines.partition_point(|line| line.start <= index).saturating_sub(1);
+      let lines = self.text_lines.iter().enumerate().skip(line.saturating_sub(rows / 2)).take(rows);
+      lines
+        .map(|(i, line)| {
+          let end = self.text_lines.get(i + 1).map_or(self.text_length, |next| next.start);
+          let words = (line.start..end).filter(|&t| !is_break(&self.text_array[t])).map(span);
+          ordered([Span::raw(" ".repeat(line.indent))].into_iter().chain(words).collect())
+        })
+        .collect()
     } else {
-      for handle in &handles {
-        handle.abort();
-        return;
+      let paragraph = self.text_paragraphs.partition_point(|&p| p <= index).saturating_sub(1);
+      let start = self.text_paragraphs.get(paragraph).copied().unwrap_or(0).max(index.saturating_sub(40));
+      let end = self.text_paragraphs.get(paragraph + 1).copied().unwrap_or(self.text_length).min(index + 60);
+      vec![ordered((start..end).map(span).collect())]
+    }
+  }
+
+  /// The current word in the big font, scaled to the largest size that fits `width` x `height`.
+  /// Returns `None` when disabled or when the word does not fit, so callers fall back to `word_line`.
+  pub fn big_word_lines(&self, width: u16, height: u16) -> Option<Vec<Line<'static>>> {
+    if !self.big_font || self.current_word().is_empty() {
+      return None;
+    }
+    let graphemes = text::visual_order(self.current_word());
+    let glyphs = graphemes.iter().map(|(_, g)| self.font.glyph(g.chars().next()?)).collect::<Option<Vec<_>>>()?;
+    let pivot = visual_pivot(&graphemes);
+    let widths: Vec<usize> = glyphs.iter().map(|g| glyph_width(g)).collect();
+    let total: usize = widths.iter().sum();
+    let orp = self.reading.orp;
+    let fits = |s: usize| {
+      let left = match orp {
+        true => (widths[..pivot].iter().sum::<usize>() + widths[pivot] / 2) * s,
+        false => total * s / 2,
+      };
+      let padded = 2 * left.max(total * s - left);
+      (padded <= width as usize && self.font.height() * s <= height as usize).then_some(left)
+    };
+    let (s, left) = (1..=self.config.big_font.max_scale).rev().find_map(|s| fits(s).map(|left| (s, left)))?;
+    let right = total * s - left;
+
+    let scaled: Vec<Vec<String>> = glyphs.iter().map(|g| font::scale(g, s)).collect();
+    let join = |glyphs: &[Vec<String>], row: usize| glyphs.iter().map(|g| g[row].as_str()).collect::<String>();
+    let lines = (0..self.font.height() * s)
+      .map(|row| {
+        Line::from(vec![
+          Span::raw(" ".repeat(right.saturating_sub(left))),
+          Span::styled(join(&scaled[..pivot], row), self.style("word")),
+          Span::styled(scaled[pivot][row].clone(), self.style(if orp { "pivot" } else { "word" })),
+          Span::styled(join(&scaled[pivot + 1..], row), self.style("word")),
+          Span::raw(" ".repeat(left.saturating_sub(right))),
+        ])
+      })
+      .collect();
+    Some(lines)
+  }
+
+  // auto-pause
+  pub fn auto_pause(&mut self) {
+    self.cancel_resume_countdown();
+    if !self.text_play_on {
+      return;
+    }
+    self.pause_text();
+    self.auto_paused = true;
+    let rewind = self.config.auto_pause.rewind_words.min(self.text_current_index);
+    if rewind > 0 {
+      self.decrement_text(rewind);
+    }
+  }
+
+  /// Reports an `Info`, `Warning` or `Error`, which `Notifications` shows.
+  pub fn notify(&self, notification: Action) {
+    if let Some(tx) = &self.action_tx {
+      tx.send(notification).ok();
+    }
+  }
+
+  pub fn auto_resume(&mut self) {
+    if !self.auto_paused || !self.config.auto_pause.resume || self.resume_handle.is_some() {
+      return;
+    }
+    self.countdown_to_play(self.config.auto_pause.resume_countdown);
+  }
+
+  /// Starts playback after counting down `seconds`, showing the remaining time.
+  pub fn countdown_to_play(&mut self, seconds: u64) {
+    self.cancel_resume_countdown();
+    if seconds == 0 {
+      self.play_text();
+      return;
+    }
+    self.resume_countdown = Some(seconds);
+    let tx = self.action_tx.clone().unwrap();
+    self.resume_handle = Some(tokio::spawn(async move {
+      for remaining in (0..seconds).rev() {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        tx.send(Action::ResumeCountdown(remaining)).unwrap();
       }
+    }));
+  }
+
+  pub fn resume_countdown(&mut self, remaining: u64) {
+    if self.resume_handle.is_none() {
+      return;
+    }
+    if remaining > 0 {
+      self.resume_countdown = Some(remaining);
+      return;
+    }
+    self.resume_countdown = None;
+    self.resume_handle = None;
+    self.play_text();
+  }
+
+  pub fn cancel_resume_countdown(&mut self) {
+    self.resume_countdown = None;
+    if let Some(handle) = self.resume_handle.take() {
+      handle.abort();
     }
   }
 
   // sreader
   pub fn text_load(&mut self) {
-    let book: String =
-      fs::read_to_string("./assets/lewisCarroll_alicesAdventuresInWonderland.txt").expect("failed to read file");
-    self.text_array = book.split_whitespace().map(|s| s.to_string()).collect();
-    self.text_current_word = self.text_array[0].clone();
+    let path = match &self.text_path {
+      Some(file) => self.resolve_book(file),
+      None => PathBuf::from(DEFAULT_BOOK),
+    };
+    let buffer = match Buffer::open(&path) {
+      Ok(buffer) => buffer,
+      Err(e) => {
+        self.notify(Action::Error(format!("Failed to read {}: {e}", path.display())));
+        return;
+      },
+    };
+    self.text_set(&path.display().to_string(), Arc::new(buffer));
+    let key = fs::canonicalize(&path).unwrap_or(path).display().to_string();
+    let position = self.state.book(&key).map(|book| book.position);
+    self.text_key = Some(key);
+    self.apply_reading_settings();
+    // Resumed through an action rather than directly, so that a recorded session starts where it did, see `session`.
+    if let Some(position) = position.filter(|_| self.config.reading.auto_resume) {
+      if let Some(tx) = &self.action_tx {
+        tx.send(Action::Seek(position)).ok();
+      }
+    }
+  }
+  pub fn text_open(&mut self, file: &str) {
+    self.text_path = Some(PathBuf::from(file));
+    self.text_load();
+  }
+  /// Finds `file` as given, or by name (with or without `.txt`) in the library directories.
+  pub fn resolve_book(&self, file: &Path) -> PathBuf {
+    if file.exists() {
+      return file.to_path_buf();
+    }
+    self
+      .config
+      .reading
+      .library
+      .iter()
+      .flat_map(|dir| [dir.join(file), dir.join(file).with_extension("txt")])
+      .find(|candidate| candidate.is_file())
+      .unwrap_or_else(|| file.to_path_buf())
+  }
+  pub fn save_position(&mut self) {
+    if let Some(key) = &self.text_key {
+      self.state.book_mut(key).position = self.text_current_index;
+      self.save_state();
+    }
+  }
+  pub fn save_state(&self) {
+    if let Err(e) = self.state.save() {
+      self.notify(Action::Error(format!("Failed to save reading state: {e}")));
+    }
+  }
+
+  // book settings
+  /// Combines the global reading settings with the overrides saved for the current book.
+  pub fn apply_reading_settings(&mut self) {
+    let mut reading = self.config.reading.clone();
+    if let Some(book) = self.text_key.as_ref().and_then(|key| self.state.book(key)) {
+      book.overrides.apply(&mut reading);
+    }
+    self.cli_overrides.apply(&mut reading);
+    // A theme picked with `t` or `SetTheme` lasts until the configured one, or the book's, changes.
+    if reading.theme != self.reading.theme {
+      self.notify(Action::SetTheme(reading.theme.clone()));
+    }
+    self.text_read_rate = reading.wpm;
+    self.hyphenator = None;
+    if reading.hyphenation.enabled {
+      match hyphenation::patterns(&reading.hyphenation.language) {
+        Some(patterns) => self.hyphenator = Some(Box::new(patterns)),
+        None => self.notify(Action::Warning(format!("No hyphenation patterns for `{}`", reading.hyphenation.language))),
+      }
+    }
+    self.reading = reading;
+    if self.text_length > 0 && self.wants_verse() != self.text_verse {
+      self.text_tokenize();
+    } else if self.text_length > 0 {
+      self.text_goto(self.text_current_index);
+    }
+  }
+  pub fn book_overrides(&self) -> Option<&ReadingOverrides> {
+    self.text_key.as_ref().and_then(|key| self.state.book(key)).map(|book| &book.overrides)
+  }
+  /// Replaces the current book's overrides, or reports that there is no book to store them with.
+  pub fn set_book_overrides(&mut self, overrides: ReadingOverrides) {
+    let Some(key) = &self.text_key else {
+      self.notify(Action::Warning("Book settings need a book loaded from a file".to_string()));
+      return;
+    };
+    // Saved for the book on purpose, so no longer held back by the command line.
+    let cli = &mut self.cli_overrides;
+    cli.wpm = cli.wpm.filter(|_| overrides.wpm.is_none());
+    cli.chunk_size = cli.chunk_size.filter(|_| overrides.chunk_size.is_none());
+    cli.theme = cli.theme.take().filter(|_| overrides.theme.is_none());
+    self.state.book_mut(key).overrides = overrides;
+    self.save_state();
+    self.apply_reading_settings();
+  }
+  pub fn adjust_wpm(&mut self, delta: i64) {
+    self.set_wpm((i64::from(self.reading.wpm) + delta).clamp(1, i64::from(u32::MAX)) as u32);
+  }
+  pub fn set_wpm(&mut self, wpm: u32) {
+    let wpm = wpm.max(1);
+    if self.text_key.is_some() {
+      let overrides = self.book_overrides().cloned().unwrap_or_default();
+      self.set_book_overrides(ReadingOverrides { wpm: Some(wpm), ..overrides });
+    } else {
+      self.reading.wpm = wpm;
+      self.text_read_rate = wpm;
+    }
+  }
+  pub fn edit_book_settings(&mut self) {
+    if self.text_key.is_none() {
+      self.notify(Action::Warning("Book settings need a book loaded from a file".to_string()));
+      return;
+    }
+    let settings = self.book_overrides().map(|overrides| overrides.to_string()).unwrap_or_default();
+    self.input = Input::new(settings);
+    self.editing_settings = true;
+    if let Some(tx) = &self.action_tx {
+      tx.send(Action::EnterInsert).unwrap();
+    }
+  }
+  pub fn complete_book_settings(&mut self, settings: &str) {
+    match settings.parse() {
+      Ok(overrides) => self.set_book_overrides(overrides),
+      Err(e) => self.notify(Action::Warning(e)),
+    }
+  }
+  pub fn text_paste(&mut self, text: &str) {
+    if text.split_whitespace().next().is_some() {
+      self.text_set("pasted text", Arc::new(Buffer::Owned(text.to_string())));
+    }
+  }
+  pub fn text_set(&mut self, source: &str, buffer: Arc<Buffer>) {
+    self.pause_text();
+    self.cancel_resume_countdown();
+    self.auto_paused = false;
+    self.text_key = None;
+    self.text_source = source.to_string();
+    self.text_looks_like_verse = text::is_verse(buffer.as_str());
+    self.text_array = Text::new(buffer);
+    self.text_current_index = 0;
+    self.text_tokenize();
+  }
+  /// Splits the loaded text again, e.g. after poetry mode changes, keeping the position on the same word. The
+  /// tokens are found by a background task and arrive in batches, see `text_receive`.
+  pub fn text_tokenize(&mut self) {
+    let word = self.text_array.iter().take(self.text_current_index).filter(|token| !is_break(token)).count();
+    let buffer = self.text_array.buffer().clone();
+    let verse = self.wants_verse();
+    self.text_verse = verse;
+    self.text_array = Text::new(buffer.clone());
+    self.text_paragraphs.clear();
+    self.text_chapters.clear();
+    self.text_chapter = None;
+    self.text_lines.clear();
+    self.text_length = 0;
+    self.text_current_index = 0;
+    self.text_chunk.clear();
+    self.text_pieces.clear();
+    self.text_seek = Some(Seek::Word(word));
+    // Dropping the receiver of an earlier load stops its task at the next batch.
+    let (batch_tx, batch_rx) = mpsc::unbounded_channel();
+    self.text_batches = Some(batch_rx);
+    let action_tx = self.action_tx.clone();
+    tokio::task::spawn_blocking(move || {
+      for batch in text::batches(buffer.as_str(), verse) {
+        if batch_tx.send(batch).is_err() {
+          return;
+        }
+        if let Some(tx) = &action_tx {
+          tx.send(Action::TextLoaded).ok();
+        }
+      }
+      // `text_receive` only sees that loading finished once the sender is gone.
+      drop(batch_tx);
+      if let Some(tx) = &action_tx {
+        tx.send(Action::TextLoaded).ok();
+      }
+    });
+  }
+  /// Adds the batches of tokens loaded so far, then moves to the position that was waiting for them.
+  pub fn text_receive(&mut self) {
+    let Some(batches) = &mut self.text_batches else {
+      return;
+    };
+    let mut received = Vec::new();
+    let finished = loop {
+      match batches.try_recv() {
+        Ok(tokens) => received.push(tokens),
+        Err(TryRecvError::Empty) => break false,
+        Err(TryRecvError::Disconnected) => break true,
+      }
+    };
+    if finished {
+      self.text_batches = None;
+    }
+    for tokens in received {
+      self.text_add(tokens);
+    }
+    self.text_resolve_seek(finished);
+  }
+  pub fn text_add(&mut self, tokens: Tokens) {
+    self.text_array.extend(&tokens.spans);
+    let chapters = tokens.paragraphs.iter().filter(|&&p| text::is_chapter(&self.text_array[p]));
+    self.text_chapters.extend(chapters);
+    self.text_paragraphs.extend(tokens.paragraphs);
+    self.text_lines.extend(tokens.lines);
     self.text_length = self.text_array.len();
   }
+  /// Moves to the position waiting for the text to be loaded, once it has been or loading `finished` without it.
+  pub fn text_resolve_seek(&mut self, finished: bool) {
+    let target = match self.text_seek {
+      Some(Seek::Token(index)) => (index < self.text_length).then_some(index),
+      Some(Seek::Word(word)) => {
+        let mut words = self.text_array.iter().enumerate().filter(|(_, token)| !is_break(token));
+        words.nth(word).map(|(index, _)| index)
+      },
+      None => return,
+    };
+    if target.is_some() || finished {
+      self.text_seek = None;
+      self.text_goto(target.unwrap_or(0));
+    }
+  }
+  pub fn wants_verse(&self) -> bool {
+    match self.reading.poetry {
+      PoetryMode::On => true,
+      PoetryMode::Off => false,
+      PoetryMode::Auto => self.text_looks_like_verse,
+    }
+  }
+  pub fn toggle_poetry(&mut self) {
+    let poetry = if self.text_verse { PoetryMode::Off } else { PoetryMode::On };
+    if self.text_key.is_some() {
+      let overrides = self.book_overrides().cloned().unwrap_or_default();
+      self.set_book_overrides(ReadingOverrides { poetry: Some(poetry), ..overrides });
+    } else {
+      self.reading.poetry = poetry;
+      self.text_tokenize();
+    }
+  }
   pub fn sread_text(&mut self, i: usize) {
     if self.text_play_on {
-      self.increment_text(i);
-      if self.text_current_index == self.text_length - 1 {
-        return;
+      if !self.next_piece() {
+        let chunk = self.text_current_index..self.chunk_end(self.text_current_index);
+        let words = chunk.filter(|&index| !is_break(&self.text_array[index])).count();
+        let on_screen = self.text_schedule.as_mut().map_or(Duration::ZERO, |schedule| schedule.shown(words));
+        self.publish(Event::Read { words, ms: u64::try_from(on_screen.as_millis()).unwrap_or(u64::MAX) });
+        self.increment_text(i);
+      }
+      let last_piece = self.text_piece + 1 >= self.text_pieces.len();
+      let loaded = self.text_batches.is_none();
+      if last_piece && loaded && self.chunk_end(self.text_current_index) >= self.text_length {
+        self.pause_text();
+        self.publish(Event::Finished);
+      } else {
+        self.schedule_next_word();
       }
-      return;
     }
   }
   pub fn increment_text(&mut self, i: usize) {
     if let Some(res) = self.text_current_index.checked_add(i) {
-      self.text_current_index = res;
-      self.text_current_word = self.text_array[self.text_current_index.clone()].clone();
+      self.text_goto(res);
+    }
+  }
+  pub fn next_paragraph(&mut self, i: usize) {
+    let next = self.text_paragraphs.partition_point(|&p| p <= self.text_current_index);
+    if i == 0 || next >= self.text_paragraphs.len() {
+      return;
     }
+    let target = (next + i - 1).min(self.text_paragraphs.len() - 1);
+    self.text_goto(self.text_paragraphs[target]);
+  }
+  pub fn previous_paragraph(&mut self, i: usize) {
+    let previous = self.text_paragraphs.partition_point(|&p| p < self.text_current_index);
+    if i == 0 || previous == 0 {
+      return;
+    }
+    self.text_goto(self.text_paragraphs[previous.saturating_sub(i)]);
+  }
+  /// Moves to the token at `index`, or to it once it has been loaded.
+  pub fn seek(&mut self, index: usize) {
+    if index < self.text_length {
+      self.text_goto(index);
+    } else if self.text_batches.is_some() {
+      self.text_seek = Some(Seek::Token(index));
+    } else {
+      self.text_goto(self.text_length.saturating_sub(1));
+    }
+  }
+  pub fn text_goto(&mut self, index: usize) {
+    if index < self.text_length {
+      self.text_current_index = index;
+      self.text_piece = 0;
+      self.text_pieces = match &self.hyphenator {
+        Some(hyphenator) if self.is_long_word(index) => {
+          hyphenation::split_word(&self.text_array[index], self.reading.hyphenation.max_length, hyphenator.as_ref())
+        },
+        _ => Vec::new(),
+      };
+      let end = self.chunk_end(index);
+      self.text_chunk = match end - index > 1 && self.text_pieces.is_empty() {
+        true => text::join((index..end).map(|i| &self.text_array[i])),
+        false => String::new(),
+      };
+      self.publish_chapter();
+      self.publish_word();
+    }
+  }
+  /// The words on screen: a piece of a long word, a chunk of several words or a single word.
+  pub fn current_word(&self) -> &str {
+    if let Some(piece) = self.text_pieces.get(self.text_piece) {
+      piece
+    } else if !self.text_chunk.is_empty() {
+      &self.text_chunk
+    } else {
+      self.text_array.get(self.text_current_index).unwrap_or_default()
+    }
+  }
+  /// Shows the next piece of a split word, returning false once the whole word has been shown.
+  pub fn next_piece(&mut self) -> bool {
+    if self.text_piece + 1 >= self.text_pieces.len() {
+      return false;
+    }
+    self.text_piece += 1;
+    self.publish_word();
+    true
+  }
+  /// Whether the token at `index` is split into pieces and so shown without the rest of its chunk.
+  pub fn is_long_word(&self, index: usize) -> bool {
+    let max_length = self.reading.hyphenation.max_length;
+    self.hyphenator.is_some()
+      && max_length > 0
+      && self.text_array.get(index).is_some_and(|word| {
+        word.chars().count() > max_length && text::token_kind(word, &self.reading.abbreviations) == TokenKind::Word
+      })
+  }
+  /// End of the chunk starting at `index`: up to `chunk_size` words, stopping at line and stanza breaks and at
+  /// words long enough to be split.
+  pub fn chunk_end(&self, index: usize) -> usize {
+    if index >= self.text_length || is_break(&self.text_array[index]) || self.is_long_word(index) {
+      return (index + 1).min(self.text_length);
+    }
+    let end = (index + self.chunk_size()).min(self.text_length);
+    (index..end).find(|&i| is_break(&self.text_array[i]) || self.is_long_word(i)).unwrap_or(end)
   }
   pub fn decrement_text(&mut self, i: usize) {
     if let Some(res) = self.text_current_index.checked_sub(i) {
-      self.text_current_index = res;
-      self.text_current_word = self.text_array[self.text_current_index.clone()].clone();
+      self.text_goto(res);
+    }
+  }
+
+  // remote control
+  pub fn publish(&self, event: Event) {
+    if let Some(events) = &self.events {
+      // Nobody may be listening.
+      events.send(event).ok();
+    }
+  }
+  pub fn publish_word(&self) {
+    let word = self.current_word();
+    if self.events.is_some() && !is_break(word) {
+      let (index, wpm) = (self.text_current_index, self.text_read_rate);
+      self.publish(Event::Word { index, word: word.to_string(), wpm });
+    }
+  }
+  /// Reports the chapter of the current word if it is not the one reported last.
+  pub fn publish_chapter(&mut self) {
+    let chapter = self.text_chapters.partition_point(|&c| c <= self.text_current_index).checked_sub(1);
+    if chapter == self.text_chapter {
+      return;
+    }
+    self.text_chapter = chapter;
+    if let Some(chapter) = chapter {
+      let position = self.text_chapters[chapter];
+      self.publish(Event::Chapter { index: chapter + 1, position, title: self.chapter_title(position) });
     }
   }
+  /// The heading paragraph starting at `position`, or its first words when it is long.
+  pub fn chapter_title(&self, position: usize) -> String {
+    let next = self.text_paragraphs.partition_point(|&p| p <= position);
+    let end = self.text_paragraphs.get(next).copied().unwrap_or(self.text_length).min(position + 12);
+    text::join((position..end).map(|i| &self.text_array[i]).filter(|token| !is_break(token)))
+  }
+}
+
+/// Optimal recognition point: the letter the eye should fixate on for a word of `len` letters.
+pub fn orp_index(len: usize) -> usize {
+  match len {
+    0..=1 => 0,
+    2..=5 => 1,
+    6..=9 => 2,
+    10..=13 => 3,
+    _ => 4,
+  }
+}
+
+/// Position in display order of the pivot of a word laid out by `text::visual_order`.
+pub fn visual_pivot(graphemes: &[(usize, &str)]) -> usize {
+  let pivot = orp_index(graphemes.len());
+  graphemes.iter().position(|(i, _)| *i == pivot).unwrap_or(0)
+}
+
+/// Multiplier for how long `word` stays on screen, taking the largest applicable pause.
+pub fn pause_multiplier(word: &str, paragraph_end: bool, reading: &ReadingConfig) -> f64 {
+  let pauses = &reading.pauses;
+  match word {
+    LINE_BREAK => return pauses.line_break,
+    STANZA_BREAK => return pauses.stanza_break,
+    _ => {},
+  }
+  let trimmed = word.trim_end_matches(['"', '\'', ')', ']', '_', '’', '”', '」', '』', '）', '】', '》']);
+  let mut multiplier: f64 = 1.0;
+  if paragraph_end {
+    multiplier = multiplier.max(pauses.paragraph);
+  }
+  let abbreviation = text::token_kind(word, &reading.abbreviations) == TokenKind::Abbreviation;
+  if trimmed.ends_with(['.', '!', '?', '。', '！', '？', '؟', '।']) && !abbreviation {
+    multiplier = multiplier.max(pauses.sentence);
+  } else if trimmed.ends_with([',', ';', ':', '、', '，', '；', '：', '،', '؛']) {
+    multiplier = multiplier.max(pauses.comma);
+  } else if trimmed.ends_with(['—', '–']) || trimmed.ends_with("--") {
+    multiplier = multiplier.max(pauses.dash);
+  }
+  if word.chars().count() > pauses.long_word_length {
+    multiplier = multiplier.max(pauses.long_word);
+  }
+  multiplier
 }
 
 impl Component for Home {
@@ -164,10 +887,50 @@ impl Component for Home {
     Ok(())
   }
 
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.big_font = config.big_font.enabled;
+    self.show_context = config.reading.context;
+    self.font = Font::load(&config.big_font.font, &config.config._config_dir).unwrap_or_else(|e| {
+      self.notify(Action::Warning(format!("{e}, falling back to the built-in font")));
+      Font::builtin()
+    });
+    self.config = config;
+    self.apply_reading_settings();
+    Ok(())
+  }
+
+  /// A load, or the tokenizing of a text in the background, is under way.
+  fn is_busy(&self) -> bool {
+    self.processing > 0 || self.text_batches.is_some()
+  }
+
+  fn theme_changed(&mut self, theme: &str) {
+    self.theme = theme.to_string();
+  }
+
+  fn mode_changed(&mut self, mode: AppMode) {
+    self.app_mode = mode;
+  }
+
+  fn modes(&self) -> &'static [AppMode] {
+    &[AppMode::Reader, AppMode::Insert]
+  }
+
+  fn init(&mut self) -> Result<()> {
+    self.state = ReadingState::load().unwrap_or_else(|e| {
+      self.notify(Action::Warning(format!("{e:#}, starting without saved positions")));
+      ReadingState::default()
+    });
+    if self.text_path.is_some() {
+      self.schedule_text_load();
+    }
+    Ok(())
+  }
+
   fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
-    self.last_events.push(key.clone());
-    let action = match self.mode {
-      Mode::Normal | Mode::Processing => return Ok(None),
+    self.last_events.push(key);
+    let action = match self.mode() {
+      Mode::Normal => return Ok(None),
       Mode::Insert => match key.code {
         KeyCode::Esc => Action::EnterNormal,
         KeyCode::Enter => {
@@ -187,36 +950,78 @@ impl Component for Home {
     Ok(Some(action))
   }
 
+  fn handle_paste_events(&mut self, text: String) -> Result<Option<Action>> {
+    let action = match self.mode() {
+      Mode::Insert => {
+        for c in text.chars() {
+          let c = if c.is_whitespace() { ' ' } else { c };
+          self.input.handle(InputRequest::InsertChar(c));
+        }
+        Action::Update
+      },
+      Mode::Normal => Action::TextPaste(text),
+    };
+    Ok(Some(action))
+  }
+
   fn update(&mut self, action: Action) -> Result<Option<Action>> {
     match action {
-      Action::Tick => self.tick(),
-      Action::Render => self.render_tick(),
-      Action::ToggleShowHelp => self.show_help = !self.show_help,
+      Action::Tick => {
+        self.tick();
+        // Turns the spinner.
+        return Ok(self.is_busy().then_some(Action::Redraw));
+      },
+      Action::Render => {
+        self.render_tick();
+        return Ok(None);
+      },
+      Action::Update => (),
       Action::ScheduleIncrementText => self.schedule_increment_text(1),
       Action::ScheduleDecrementText => self.schedule_decrement_text(1),
       Action::ScheduleTextLoad => self.schedule_text_load(),
-      Action::ScheduleSreadText => self.schedule_sread_text(1),
+      Action::ScheduleSreadText => self.schedule_sread_text(),
+      Action::Play => self.play(),
+      Action::Pause => self.pause(),
+      Action::Seek(i) => self.seek(i),
       Action::IncrementText(i) => self.increment_text(i),
       Action::DecrementText(i) => self.decrement_text(i),
+      Action::NextParagraph(i) => self.next_paragraph(i),
+      Action::PreviousParagraph(i) => self.previous_paragraph(i),
+      Action::PendingCount(count) => self.pending_count = count,
+      Action::ToggleBigFont => self.big_font = !self.big_font,
       Action::TextLoad() => self.text_load(),
+      Action::TextOpen(file) => self.text_open(&file),
+      Action::TextLoaded => self.text_receive(),
+      Action::TextPaste(s) => self.text_paste(&s),
       Action::SreadText(i) => self.sread_text(i),
+      Action::Quit => self.save_position(),
+      Action::FocusLost if self.config.auto_pause.on_focus_lost => self.auto_pause(),
+      Action::Suspend if self.config.auto_pause.on_suspend => self.auto_pause(),
+      Action::Resize(_, _) if self.config.auto_pause.on_resize => {
+        self.auto_pause();
+        self.auto_resume();
+      },
+      Action::FocusGained | Action::Resume => self.auto_resume(),
+      Action::ResumeCountdown(remaining) => self.resume_countdown(remaining),
+      Action::IncreaseWpm(i) => self.adjust_wpm(i64::from(i)),
+      Action::DecreaseWpm(i) => self.adjust_wpm(-i64::from(i)),
+      Action::SetWpm(i) => self.set_wpm(i),
+      Action::EditBookSettings => self.edit_book_settings(),
+      Action::TogglePoetry => self.toggle_poetry(),
+      Action::ToggleContext => self.show_context = !self.show_context,
+      Action::ResetBookSettings => self.set_book_overrides(ReadingOverrides::default()),
+      Action::CompleteInput(s) if self.editing_settings => self.complete_book_settings(&s),
       Action::CompleteInput(s) => self.add(s),
       Action::EnterNormal => {
-        self.mode = Mode::Normal;
-      },
-      Action::EnterInsert => {
-        self.mode = Mode::Insert;
-      },
-      Action::EnterProcessing => {
-        self.mode = Mode::Processing;
-      },
-      Action::ExitProcessing => {
-        // TODO: Make this go to previous mode instead
-        self.mode = Mode::Normal;
+        if std::mem::take(&mut self.editing_settings) {
+          self.input.reset();
+        }
       },
-      _ => (),
+      Action::EnterProcessing => self.processing += 1,
+      Action::ExitProcessing => self.processing = self.processing.saturating_sub(1),
+      _ => return Ok(None),
     }
-    Ok(None)
+    Ok(Some(Action::Redraw))
   }
 
   // UI
@@ -233,106 +1038,430 @@ impl Component for Home {
     //    text.insert(0, format!("Counter: {}", self.counter).into());
 
     text.insert(0, "".into());
+    if let Some(remaining) = self.resume_countdown {
+      text.insert(0, Span::styled(format!("Resuming in {remaining}…"), self.style("dim")).into());
+    } else if self.auto_paused {
+      text.insert(0, Span::styled("Paused", self.style("dim")).into());
+    } else {
+      text.insert(0, "".into());
+    }
+    text.insert(
+      0,
+      format!(
+        "Current Word: {}/{} @ {} wpm{}{}{}",
+        self.text_current_index,
+        self.text_length,
+        self.text_read_rate,
+        match &self.text_schedule {
+          Some(s) if s.measured_wpm() > 0.0 => {
+            format!(" (measured {:.0} / target {:.0})", s.measured_wpm(), s.target_wpm())
+          },
+          _ => String::new(),
+        },
+        if self.text_verse { " (poetry)" } else { "" },
+        if self.book_overrides().is_some_and(|o| !o.is_empty()) { " (book settings)" } else { "" },
+      )
+      .into(),
+    );
     text.insert(0, "".into());
-    text.insert(0, format!("Current Word: {}/{}", self.text_current_index, self.text_length).into());
-    text.insert(0, "".into());
-    text.insert(0, format!("{}", self.text_current_word).into());
+    let inner = rects[0].inner(&Margin { horizontal: 1, vertical: 1 });
+    match self.big_word_lines(inner.width, inner.height.saturating_sub(9)) {
+      Some(lines) => {
+        for line in lines.into_iter().rev() {
+          text.insert(0, line);
+        }
+      },
+      None => text.insert(0, self.word_line()),
+    }
     text.insert(0, "".into());
     text.insert(0, "".into());
     text.insert(
       0,
       Line::from(vec![
         "Press ".into(),
-        Span::styled("j", Style::default().fg(Color::Red)),
+        Span::styled("j", self.style("key")),
         " or ".into(),
-        Span::styled("k", Style::default().fg(Color::Red)),
+        Span::styled("k", self.style("key")),
         " to ".into(),
-        Span::styled("increment", Style::default().fg(Color::Yellow)),
+        Span::styled("increment", self.style("highlight")),
         " or ".into(),
-        Span::styled("decrement", Style::default().fg(Color::Yellow)),
+        Span::styled("decrement", self.style("highlight")),
         ".".into(),
       ]),
     );
     text.insert(0, "".into());
 
-    f.render_widget(
-      Paragraph::new(text)
-        .block(
-          Block::default()
-            .title("sreader")
-            .title_alignment(Alignment::Center)
-            .borders(Borders::ALL)
-            .border_style(match self.mode {
-              Mode::Processing => Style::default().fg(Color::Yellow),
-              _ => Style::default(),
-            })
-            .border_type(BorderType::Rounded),
-        )
-        .style(Style::default().fg(Color::Cyan))
-        .alignment(Alignment::Center),
-      rects[0],
-    );
+    let mut block = Block::default()
+      .title(match self.text_source.as_str() {
+        "" => "sreader".to_string(),
+        source => format!("sreader - {source}"),
+      })
+      .title_alignment(Alignment::Center)
+      .borders(Borders::ALL)
+      .border_style(match self.is_busy() {
+        true => self.style("border_processing"),
+        false => self.style("border"),
+      })
+      .border_type(BorderType::Rounded);
+    // Reading and typing go on while loading, so this is all that shows it.
+    if self.is_busy() {
+      let spinner = format!("{} Loading", SPINNER[self.app_ticker % SPINNER.len()]);
+      let title = ratatui::widgets::block::Title::from(Span::styled(spinner, self.style("border_processing")));
+      block = block.title(title.alignment(Alignment::Left));
+    }
+    f.render_widget(Paragraph::new(text).block(block).style(self.style("text")).alignment(Alignment::Center), rects[0]);
+
+    if self.text_length > 0 {
+      let inner = rects[0].inner(&Margin { horizontal: 2, vertical: 1 });
+      let progress = LineGauge::default()
+        .ratio(self.text_current_index as f64 / self.text_length.saturating_sub(1).max(1) as f64)
+        .label(format!("{:>3.0}%", 100.0 * self.text_current_index as f64 / self.text_length as f64))
+        .style(self.style("text"))
+        .gauge_style(self.style("progress"))
+        .line_set(symbols::line::THICK);
+      f.render_widget(progress, Rect { y: inner.bottom().saturating_sub(1), height: 1.min(inner.height), ..inner });
+    }
+
+    if self.show_context && self.text_length > 0 {
+      let inner = rects[0].inner(&Margin { horizontal: 2, vertical: 1 });
+      let height = (inner.height / 3).min(8);
+      let area = Rect { y: inner.bottom().saturating_sub(height + 1), height, ..inner };
+      let context = Paragraph::new(self.context_lines(height.saturating_sub(1) as usize))
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::TOP).border_style(self.style("border")));
+      f.render_widget(Clear, area);
+      f.render_widget(context, area);
+    }
+
     let width = rects[1].width.max(3) - 3; // keep 2 for borders and 1 for cursor
     let scroll = self.input.visual_scroll(width as usize);
     let input = Paragraph::new(self.input.value())
-      .style(match self.mode {
-        Mode::Insert => Style::default().fg(Color::Yellow),
+      .style(match self.mode() {
+        Mode::Insert => self.style("input"),
         _ => Style::default(),
       })
       .scroll((0, scroll as u16))
-      .block(Block::default().borders(Borders::ALL).title(Line::from(vec![
-        Span::raw("Enter Input Mode "),
-        Span::styled("(Press ", Style::default().fg(Color::DarkGray)),
-        Span::styled("/", Style::default().add_modifier(Modifier::BOLD).fg(Color::Gray)),
-        Span::styled(" to start, ", Style::default().fg(Color::DarkGray)),
-        Span::styled("ESC", Style::default().add_modifier(Modifier::BOLD).fg(Color::Gray)),
-        Span::styled(" to finish)", Style::default().fg(Color::DarkGray)),
-      ])));
+      .block(Block::default().borders(Borders::ALL).border_style(self.style("border")).title(
+        match self.editing_settings {
+          true => Line::from(vec![
+            Span::raw("Book Settings "),
+            Span::styled("(e.g. ", self.style("dim")),
+            Span::styled("wpm=400 chunk_size=2 theme=light pauses.sentence=2.5", self.style("key")),
+            Span::styled(", empty to reset)", self.style("dim")),
+          ]),
+          false => Line::from(vec![
+            Span::raw("Enter Input Mode "),
+            Span::styled("(Press ", self.style("dim")),
+            Span::styled("/", self.style("key")),
+            Span::styled(" to start, ", self.style("dim")),
+            Span::styled("ESC", self.style("key")),
+            Span::styled(" to finish)", self.style("dim")),
+          ]),
+        },
+      ));
     f.render_widget(input, rects[1]);
-    if self.mode == Mode::Insert {
+    if self.mode() == Mode::Insert {
       f.set_cursor((rects[1].x + 1 + self.input.cursor() as u16).min(rects[1].x + rects[1].width - 2), rects[1].y + 1)
     }
 
-    if self.show_help {
-      let rect = rect.inner(&Margin { horizontal: 4, vertical: 2 });
-      f.render_widget(Clear, rect);
-      let block = Block::default()
-        .title(Line::from(vec![Span::styled("Key Bindings", Style::default().add_modifier(Modifier::BOLD))]))
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow));
-      f.render_widget(block, rect);
-      let rows = vec![
-        Row::new(vec!["?", "Open Help"]),
-        Row::new(vec!["l", "Load Text"]),
-        Row::new(vec!["Space", "Play/Pause Text"]),
-        Row::new(vec!["j", "Increment Text"]),
-        Row::new(vec!["k", "Decrement Text"]),
-        Row::new(vec![""]),
-        Row::new(vec!["/", "Enter Input"]),
-        Row::new(vec!["ESC", "Exit Input"]),
-        Row::new(vec!["Enter", "Submit Input"]),
-        Row::new(vec!["q", "Quit"]),
-      ];
-      let table = Table::new(rows)
-        .header(Row::new(vec!["Key", "Action"]).bottom_margin(1).style(Style::default().add_modifier(Modifier::BOLD)))
-        .widths(&[Constraint::Percentage(10), Constraint::Percentage(90)])
-        .column_spacing(1);
-      f.render_widget(table, rect.inner(&Margin { vertical: 4, horizontal: 2 }));
-    };
-
+    let mut status = Block::default()
+      .title(
+        ratatui::widgets::block::Title::from(format!(
+          "{:?}",
+          &self.last_events.iter().map(key_event_to_string).collect::<Vec<_>>()
+        ))
+        .alignment(Alignment::Right),
+      )
+      .title_style(self.style("status"));
+    if self.pending_count > 0 {
+      status = status.title(ratatui::widgets::block::Title::from(self.pending_count.to_string()));
+    }
     f.render_widget(
-      Block::default()
-        .title(
-          ratatui::widgets::block::Title::from(format!(
-            "{:?}",
-            &self.last_events.iter().map(|k| key_event_to_string(k)).collect::<Vec<_>>()
-          ))
-          .alignment(Alignment::Right),
-        )
-        .title_style(Style::default().add_modifier(Modifier::BOLD)),
+      status,
       Rect { x: rect.x + 1, y: rect.height.saturating_sub(1), width: rect.width.saturating_sub(2), height: 1 },
     );
 
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use clap::Parser;
+
+  use super::*;
+  use crate::{cli::Cli, components::stats::Stats, config::ReadingConfig};
+
+  #[test]
+  fn test_pause_multiplier() {
+    let reading = ReadingConfig::default();
+    let pauses = &reading.pauses;
+    assert_eq!(pause_multiplier("word", false, &reading), 1.0);
+    assert_eq!(pause_multiplier("word,", false, &reading), pauses.comma);
+    assert_eq!(pause_multiplier("end.\"", false, &reading), pauses.sentence);
+    assert_eq!(pause_multiplier("end?", true, &reading), pauses.paragraph);
+    assert_eq!(pause_multiplier("extraordinary", false, &reading), pauses.long_word);
+    assert_eq!(pause_multiplier("ある。」", false, &reading), pauses.sentence);
+    assert_eq!(pause_multiplier("مرحبا،", false, &reading), pauses.comma);
+    assert_eq!(pause_multiplier("Mr.", false, &reading), 1.0);
+    assert_eq!(pause_multiplier("3.14", false, &reading), 1.0);
+    assert_eq!(pause_multiplier("Hatter—", false, &reading), pauses.dash);
+    assert_eq!(pause_multiplier("said--", false, &reading), pauses.dash);
+  }
+
+  #[test]
+  fn test_word_line_alignment() {
+    let mut home = Home::new();
+    home.apply_reading_settings();
+    let spans = |home: &Home| home.word_line().spans.into_iter().map(|s| s.content.to_string()).collect::<Vec<_>>();
+    home.text_chunk = "東京です".to_string();
+    assert_eq!(spans(&home), vec!["  ", "東", "京", "です", ""]);
+    home.text_chunk = "שלום".to_string();
+    assert_eq!(spans(&home), vec!["", "םו", "ל", "ש", " "]);
+  }
+
+  fn home(reading: ReadingConfig) -> Home {
+    let mut home = Home::new();
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    home.register_action_handler(tx).unwrap();
+    home.config.reading = reading;
+    home.apply_reading_settings();
+    home
+  }
+
+  /// Waits for the background task started by `text_tokenize` to deliver every batch.
+  async fn loaded(home: &mut Home) {
+    while home.text_batches.is_some() {
+      tokio::time::sleep(Duration::from_millis(1)).await;
+      home.text_receive();
+    }
+  }
+
+  #[tokio::test]
+  async fn test_long_word_pieces() {
+    let mut home = home(ReadingConfig { chunk_size: 3, ..ReadingConfig::default() });
+    home.text_paste("Curiouser and incomprehensibilities cried Alice");
+    loaded(&mut home).await;
+    assert_eq!(home.current_word(), "Curiouser and");
+    home.text_play_on = true;
+    home.sread_text(2);
+    assert_eq!(home.current_word(), "incomprehen-");
+    home.sread_text(1);
+    assert_eq!(home.current_word(), "sibilities");
+    home.sread_text(1);
+    assert_eq!(home.current_word(), "cried Alice");
+    assert!(!home.text_play_on);
+  }
+
+  #[tokio::test]
+  async fn test_redraw_requests() {
+    let mut home = home(ReadingConfig::default());
+    assert_eq!(home.update(Action::Tick).unwrap(), None);
+    assert_eq!(home.update(Action::Render).unwrap(), None);
+    assert_eq!(home.update(Action::ToggleContext).unwrap(), Some(Action::Redraw));
+    assert_eq!(home.update(Action::PendingCount(3)).unwrap(), Some(Action::Redraw));
+    assert_eq!(home.update(Action::ReloadConfig).unwrap(), None);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_remote_events() {
+    let (events, mut rx) = broadcast::channel(64);
+    let mut home = home(ReadingConfig { poetry: PoetryMode::Off, ..ReadingConfig::default() }).events(events);
+    home.text_paste("CHAPTER I.\n\nAlice was tired.\n\nCHAPTER II.\nThe Pool\n\nCuriouser!");
+    loaded(&mut home).await;
+    home.update(Action::Seek(6)).unwrap();
+    home.update(Action::SetWpm(400)).unwrap();
+    home.text_play_on = true;
+    home.sread_text(1);
+    home.sread_text(1);
+    let mut received = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+      received.push(event);
+    }
+    let word = |index: usize, word: &str, wpm| Event::Word { index, word: word.into(), wpm };
+    let chapter = |index, position, title: &str| Event::Chapter { index, position, title: title.into() };
+    assert_eq!(received, vec![
+      chapter(1, 0, "CHAPTER I."),
+      word(0, "CHAPTER", 300),
+      chapter(2, 5, "CHAPTER II. The Pool"),
+      word(6, "II.", 300),
+      Event::Read { words: 1, ms: 0 },
+      word(7, "The", 400),
+      Event::Read { words: 1, ms: 0 },
+      word(8, "Pool", 400),
+    ]);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_words_read() {
+    let (events, rx) = broadcast::channel(64);
+    let mut stats = Stats::new(rx);
+    let mut home = home(ReadingConfig { chunk_size: 3, ..ReadingConfig::default() }).events(events);
+    home.text_paste("Alice was beginning to get incomprehensibly tired of sitting by her sister");
+    loaded(&mut home).await;
+    // Moving around is not reading.
+    home.update(Action::IncrementText(3)).unwrap();
+    home.update(Action::DecrementText(3)).unwrap();
+    home.text_play_on = true;
+    // Chunks stop short of the long word, whose pieces count once it has been read in full.
+    for _ in 0..5 {
+      home.sread_text(home.chunk_end(home.text_current_index) - home.text_current_index);
+    }
+    assert_eq!(home.current_word(), "by her sister");
+    stats.receive();
+    assert_eq!(stats.words, 9);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_playback_timing() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut home = Home::new();
+    home.register_action_handler(tx).unwrap();
+    home.config.reading = ReadingConfig { wpm: 600, chunk_size: 1, ..ReadingConfig::default() };
+    home.apply_reading_settings();
+    home.text_paste(&"word ".repeat(10_000));
+    loaded(&mut home).await;
+    let start = tokio::time::Instant::now();
+    home.play_text();
+    let (mut measured, mut target) = (0.0, 0.0);
+    while home.text_play_on {
+      let action = rx.recv().await.unwrap();
+      // Handling actions and drawing take time, which must not push back the words after them.
+      tokio::time::sleep(Duration::from_millis(7)).await;
+      home.update(action).unwrap();
+      if let Some(schedule) = &home.text_schedule {
+        (measured, target) = (schedule.measured_wpm(), schedule.target_wpm());
+      }
+    }
+    // The last word stays on screen, so 9,999 words were replaced, each after 100ms.
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(999_900) && elapsed < Duration::from_millis(999_950), "{elapsed:?}");
+    assert_eq!(home.text_current_index, 9_999);
+    assert_eq!(target, 600.0);
+    assert!((measured - 600.0).abs() < 0.1, "{measured}");
+  }
+
+  #[tokio::test]
+  async fn test_background_load() {
+    let mut home = home(ReadingConfig { poetry: PoetryMode::Off, ..ReadingConfig::default() });
+    let book = "Alice was beginning\nto get very tired\n\nof sitting by her sister\n".repeat(2000);
+    home.text_paste(&book);
+    home.text_seek = Some(Seek::Token(12_000));
+    assert_eq!(home.current_word(), "");
+    loaded(&mut home).await;
+    assert_eq!(home.text_length, 24_000);
+    assert_eq!((home.text_current_index, home.current_word()), (12_000, "Alice"));
+    assert_eq!(home.text_paragraphs.len(), 2001);
+
+    home.toggle_poetry();
+    loaded(&mut home).await;
+    assert!(home.text_verse);
+    assert_eq!(home.current_word(), "Alice");
+    assert_eq!(home.text_array.iter().filter(|token| !is_break(token)).count(), 24_000);
+  }
+
+  #[tokio::test]
+  async fn test_loaded_actions() {
+    let mut home = Home::new();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    home.register_action_handler(tx).unwrap();
+    home.text_paste(&"Alice was beginning to get very tired\n\n".repeat(20_000));
+    // Handling `TextLoaded` alone finishes loading, which needs the last one sent after the batches are done.
+    let finish = async {
+      while home.text_batches.is_some() {
+        if let Some(Action::TextLoaded) = rx.recv().await {
+          home.text_receive();
+        }
+      }
+    };
+    tokio::time::timeout(Duration::from_secs(10), finish).await.expect("loading finishes");
+    assert_eq!(home.text_array.iter().filter(|token| !is_break(token)).count(), 140_000);
+  }
+
+  #[test]
+  fn test_failures_are_notified() {
+    let mut home = Home::new().file(Some(PathBuf::from("no/such/book.txt")));
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    home.register_action_handler(tx).unwrap();
+    home.text_load();
+    let error = rx.try_recv();
+    assert!(matches!(&error, Ok(Action::Error(message)) if message.starts_with("Failed to read no/such/book.txt")));
+    home.edit_book_settings();
+    assert_eq!(rx.try_recv(), Ok(Action::Warning("Book settings need a book loaded from a file".to_string())));
+  }
+
+  #[tokio::test]
+  async fn test_open_unreadable_book() {
+    let mut home = Home::new();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    home.register_action_handler(tx).unwrap();
+    home.text_paste("Down the Rabbit-Hole");
+    loaded(&mut home).await;
+    while rx.try_recv().is_ok() {}
+    // Neither a mistyped path nor a directory takes the book being read away.
+    for path in ["no/such/book.txt", "assets"] {
+      home.update(Action::TextOpen(path.into())).unwrap();
+      let error = rx.try_recv();
+      assert!(matches!(&error, Ok(Action::Error(message)) if message.starts_with(&format!("Failed to read {path}"))));
+      assert_eq!(home.current_word(), "Down");
+    }
+  }
+
+  #[test]
+  fn test_reading_settings_layers() {
+    let args = Cli::try_parse_from([env!("CARGO_PKG_NAME"), "--wpm", "500"]).unwrap();
+    let mut home = Home::new().overrides(args.reading_overrides());
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    home.register_action_handler(tx).unwrap();
+    // The themes asked for since last looked at.
+    let mut themes = || {
+      let actions = std::iter::from_fn(|| rx.try_recv().ok()).collect::<Vec<_>>();
+      actions.into_iter().filter(|action| matches!(action, Action::SetTheme(_))).collect::<Vec<_>>()
+    };
+    let key = "no/such/layered.txt";
+    home.text_key = Some(key.into());
+    let overrides = ReadingOverrides { wpm: Some(400), chunk_size: Some(2), ..Default::default() };
+    home.state.book_mut(key).overrides = ReadingOverrides { theme: Some("light".into()), ..overrides };
+    home.apply_reading_settings();
+    // The flag wins over the book, which wins over the config.
+    assert_eq!((home.reading.wpm, home.reading.chunk_size), (500, 2));
+    assert_eq!(themes(), vec![Action::SetTheme("light".into())]);
+
+    // A theme cycled to outlasts reapplying the same settings, as they ask for no other.
+    home.apply_reading_settings();
+    assert_eq!(themes(), vec![]);
+    // Changing the speed saves it for the book, over the flag.
+    home.update(Action::IncreaseWpm(10)).unwrap();
+    assert_eq!(home.reading.wpm, 510);
+    assert_eq!(themes(), vec![]);
+    home.update(Action::ResetBookSettings).unwrap();
+    assert_eq!((home.reading.wpm, home.reading.chunk_size), (300, 1));
+    assert_eq!(themes(), vec![Action::SetTheme("dark".into())]);
+  }
+
+  #[test]
+  fn test_processing_keeps_mode() {
+    let mut home = Home::new();
+    let key = |c| KeyEvent::from(KeyCode::Char(c));
+    home.mode_changed(AppMode::Insert);
+    home.update(Action::EnterProcessing).unwrap();
+    assert!(home.is_busy());
+    // Ticks turn the spinner.
+    assert_eq!(home.update(Action::Tick).unwrap(), Some(Action::Redraw));
+    // Typing goes on while loading, and loading finishing does not end it.
+    home.handle_key_events(key('a')).unwrap();
+    home.update(Action::ExitProcessing).unwrap();
+    home.handle_key_events(key('b')).unwrap();
+    assert_eq!((home.mode(), home.input.value()), (Mode::Insert, "ab"));
+    assert!(!home.is_busy());
+
+    // Leaving insert mode while loading stays in normal mode once done.
+    home.update(Action::EnterProcessing).unwrap();
+    home.mode_changed(AppMode::Reader);
+    assert_eq!(home.mode(), Mode::Normal);
+    home.update(Action::ExitProcessing).unwrap();
+    assert_eq!((home.mode(), home.processing), (Mode::Normal, 0));
+  }
+}
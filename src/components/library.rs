@@ -0,0 +1,162 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::{action::Action, app::Mode, config::Config, state::ReadingState};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Book {
+  pub title: String,
+  pub path: PathBuf,
+  /// Where reading left off, for books opened before.
+  pub position: Option<usize>,
+}
+
+/// The books in the `reading.library` directories and those read before, to pick one to read with `j`, `k` and
+/// `Enter` while `Mode::Library` is open.
+#[derive(Default)]
+pub struct Library {
+  pub config: Config,
+  pub theme: String,
+  pub action_tx: Option<UnboundedSender<Action>>,
+  pub books: Vec<Book>,
+  pub list: ListState,
+}
+
+impl Library {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Finds the books again, as positions change while reading.
+  pub fn refresh(&mut self) {
+    let state = ReadingState::load().unwrap_or_default();
+    let mut books = BTreeMap::new();
+    let found = self.config.reading.library.iter().filter_map(|dir| fs::read_dir(dir).ok()).flatten().flatten();
+    for path in found.map(|entry| entry.path()).filter(|path| path.extension().is_some_and(|ext| ext == "txt")) {
+      books.insert(fs::canonicalize(&path).unwrap_or(path), None);
+    }
+    for (key, book) in &state.books {
+      let path = PathBuf::from(key);
+      if path.is_file() {
+        books.insert(path, Some(book.position));
+      }
+    }
+    self.books = books
+      .into_iter()
+      .map(|(path, position)| {
+        let title = path.file_stem().unwrap_or(path.as_os_str()).to_string_lossy().to_string();
+        Book { title, path, position }
+      })
+      .collect();
+    self.books.sort_by(|a, b| a.title.cmp(&b.title));
+    let selected = self.list.selected().unwrap_or_default().min(self.books.len().saturating_sub(1));
+    self.list.select((!self.books.is_empty()).then_some(selected));
+  }
+
+  pub fn select_next(&mut self) {
+    if let Some(i) = self.list.selected() {
+      self.list.select(Some((i + 1).min(self.books.len() - 1)));
+    }
+  }
+
+  pub fn select_previous(&mut self) {
+    if let Some(i) = self.list.selected() {
+      self.list.select(Some(i.saturating_sub(1)));
+    }
+  }
+
+  /// Opens the selected book in the reader and goes back to it.
+  pub fn open(&mut self) -> Option<Action> {
+    let book = &self.books[self.list.selected()?];
+    if let Some(tx) = &self.action_tx {
+      tx.send(Action::TextOpen(book.path.display().to_string())).ok();
+    }
+    Some(Action::PopMode)
+  }
+
+  pub fn style(&self, name: &str) -> Style {
+    self.config.style(Mode::Library, &self.theme, name)
+  }
+}
+
+impl Component for Library {
+  fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+    self.action_tx = Some(tx);
+    Ok(())
+  }
+
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.config = config;
+    Ok(())
+  }
+
+  fn theme_changed(&mut self, theme: &str) {
+    self.theme = theme.to_string();
+  }
+
+  fn modes(&self) -> &'static [Mode] {
+    &[Mode::Library]
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    match key.code {
+      KeyCode::Char('j') | KeyCode::Down => self.select_next(),
+      KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
+      KeyCode::Enter => return Ok(self.open()),
+      _ => return Ok(None),
+    }
+    Ok(Some(Action::Redraw))
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::PushMode(Mode::Library) => {
+        self.refresh();
+        Ok(Some(Action::Redraw))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    let block = Block::default()
+      .title("Library")
+      .title_alignment(Alignment::Center)
+      .borders(Borders::ALL)
+      .border_style(self.style("border"))
+      .border_type(BorderType::Rounded);
+    let inner = block.inner(rect);
+    f.render_widget(Clear, rect);
+    f.render_widget(block, rect);
+    let rects = Layout::default().constraints([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+    let hint = Line::from(vec![
+      Span::styled("j/k", self.style("key")),
+      Span::styled(" select, ", self.style("dim")),
+      Span::styled("Enter", self.style("key")),
+      Span::styled(" read, ", self.style("dim")),
+      Span::styled("ESC", self.style("key")),
+      Span::styled(" back", self.style("dim")),
+    ]);
+    f.render_widget(Paragraph::new(hint).alignment(Alignment::Center), rects[1]);
+    if self.books.is_empty() {
+      let empty = Span::styled("No books found, add directories to `reading.library` in the config", self.style("dim"));
+      f.render_widget(Paragraph::new(empty).alignment(Alignment::Center), rects[0]);
+      return Ok(());
+    }
+    let items = self.books.iter().map(|book| {
+      let mut line = vec![Span::styled(book.title.as_str(), self.style("text"))];
+      if let Some(position) = book.position {
+        line.push(Span::styled(format!("  at word {position}"), self.style("dim")));
+      }
+      ListItem::new(Line::from(line))
+    });
+    let list = List::new(items.collect::<Vec<_>>()).highlight_symbol("> ").highlight_style(self.style("highlight"));
+    f.render_stateful_widget(list, rects[0], &mut self.list);
+    Ok(())
+  }
+}
@@ -0,0 +1,182 @@
+use std::sync::{Arc, Mutex};
+
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tracing::Level;
+
+use super::Component;
+use crate::{
+  action::Action,
+  app::Mode,
+  config::Config,
+  tui::Frame,
+  utils::{LogRecord, LogRecords, LOG_RECORDS},
+};
+
+/// Share of the screen the pane takes up.
+const PANE_PERCENT: u16 = 40;
+/// Rows of the input box below the reader, which the pane leaves uncovered.
+const INPUT_HEIGHT: u16 = 3;
+
+/// Tails the most recent log records in a pane over the lower part of the reader, toggled by `ToggleLogPane`.
+pub struct LogPane {
+  pub config: Config,
+  pub theme: String,
+  pub records: Arc<Mutex<LogRecords>>,
+  pub visible: bool,
+  /// `LogRecords::total` when the pane was last drawn.
+  pub drawn: u64,
+}
+
+impl Default for LogPane {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl LogPane {
+  pub fn new() -> Self {
+    Self::with_records(LOG_RECORDS.clone())
+  }
+
+  pub fn with_records(records: Arc<Mutex<LogRecords>>) -> Self {
+    Self { config: Config::default(), theme: String::new(), records, visible: false, drawn: 0 }
+  }
+
+  fn total(&self) -> u64 {
+    self.records.lock().map_or(0, |records| records.total)
+  }
+
+  pub fn style(&self, name: &str) -> Style {
+    self.config.style(Mode::Reader, &self.theme, name)
+  }
+
+  fn line<'a>(&self, record: &'a LogRecord) -> Line<'a> {
+    let style = match record.level {
+      Level::ERROR => self.style("error"),
+      Level::WARN => self.style("warning"),
+      Level::INFO => self.style("info"),
+      _ => self.style("dim"),
+    };
+    Line::from(vec![
+      Span::styled(format!("{:<5} ", record.level), style),
+      Span::styled(format!("{}: ", record.target), self.style("dim")),
+      Span::raw(record.message.as_str()),
+    ])
+  }
+}
+
+impl Component for LogPane {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.config = config;
+    Ok(())
+  }
+
+  fn theme_changed(&mut self, theme: &str) {
+    self.theme = theme.to_string();
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::ToggleLogPane => {
+        self.visible = !self.visible;
+        Ok(Some(Action::Redraw))
+      },
+      Action::Tick if self.visible && self.total() != self.drawn => Ok(Some(Action::Redraw)),
+      _ => Ok(None),
+    }
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    if !self.visible {
+      return Ok(());
+    }
+    let bottom = rect.bottom().saturating_sub(INPUT_HEIGHT).max(rect.y);
+    let height = (rect.height * PANE_PERCENT / 100).min(bottom - rect.y);
+    let area = Rect::new(rect.x, bottom - height, rect.width, height);
+    let block = Block::default()
+      .title(Span::styled("Log", Style::default().add_modifier(Modifier::BOLD)))
+      .borders(Borders::ALL)
+      .border_style(self.style("help"));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+    let Ok(records) = self.records.lock() else {
+      return Ok(());
+    };
+    self.drawn = records.total;
+    if records.records.is_empty() {
+      f.render_widget(Paragraph::new(Span::styled("Nothing logged so far", self.style("dim"))), inner);
+      return Ok(());
+    }
+    // One row per record, the newest at the bottom.
+    let first = records.records.len().saturating_sub(usize::from(inner.height));
+    let lines = records.records.range(first..).map(|record| self.line(record)).collect::<Vec<_>>();
+    f.render_widget(Paragraph::new(lines), inner);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use insta::assert_snapshot;
+  use pretty_assertions::assert_eq;
+  use tracing_subscriber::prelude::*;
+
+  use super::*;
+  use crate::{
+    tui::{Backend, Tui},
+    utils::RecordsLayer,
+  };
+
+  #[test]
+  fn test_records() {
+    let records = Arc::new(Mutex::new(LogRecords::default()));
+    let subscriber = tracing_subscriber::registry().with(RecordsLayer::new(records.clone()));
+    tracing::subscriber::with_default(subscriber, || {
+      tracing::info!("Reloaded configuration");
+      tracing::warn!(language = "xx", "No hyphenation patterns");
+    });
+    let records = records.lock().unwrap();
+    assert_eq!(records.total, 2);
+    assert_eq!(
+      records.records,
+      [
+        LogRecord {
+          level: Level::INFO,
+          target: "ratatui_counter::components::logs::tests".into(),
+          message: "Reloaded configuration".into(),
+        },
+        LogRecord {
+          level: Level::WARN,
+          target: "ratatui_counter::components::logs::tests".into(),
+          message: "No hyphenation patterns language=\"xx\"".into(),
+        },
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_draw() {
+    let records = Arc::new(Mutex::new(LogRecords::default()));
+    let mut pane = LogPane::with_records(records.clone());
+    assert_eq!(pane.update(Action::ToggleLogPane).unwrap(), Some(Action::Redraw));
+    for i in 0..8 {
+      let level = if i == 7 { Level::ERROR } else { Level::DEBUG };
+      let message = format!("Record {i}");
+      records.lock().unwrap().push(LogRecord { level, target: "ratatui_counter::app".into(), message });
+    }
+    assert_eq!(pane.update(Action::Tick).unwrap(), Some(Action::Redraw));
+
+    let mut tui = Tui::test(60, 15).unwrap();
+    tui.draw(|f| pane.draw(f, f.size()).unwrap()).unwrap();
+    assert_eq!(pane.update(Action::Tick).unwrap(), None);
+    let Backend::Test(backend) = tui.backend() else {
+      unreachable!("`Tui::test` draws to a `TestBackend`");
+    };
+    let buffer = backend.buffer();
+    let rows = (0..buffer.area.height)
+      .map(|y| (0..buffer.area.width).map(|x| buffer.get(x, y).symbol.as_str()).collect::<String>());
+    assert_snapshot!("log_pane", rows.map(|row| row.trim_end().to_string()).collect::<Vec<_>>().join("\n"));
+  }
+}
@@ -0,0 +1,309 @@
+use std::collections::VecDeque;
+
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+use super::Component;
+use crate::{
+  action::Action,
+  app::Mode,
+  config::{Config, NotificationsConfig},
+  tui::Frame,
+};
+
+/// Toasts shown at once, older ones giving way to newer ones.
+const MAX_TOASTS: usize = 3;
+/// Warnings and errors kept for the error log.
+const LOG_LIMIT: usize = 200;
+const TOAST_WIDTH: u16 = 48;
+/// Lines of a message shown in a toast, the rest are in the error log.
+const TOAST_LINES: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+  Info,
+  Warning,
+  Error,
+}
+
+impl Severity {
+  /// The severity and message of `Info`, `Warning` and `Error` actions.
+  pub fn of(action: &Action) -> Option<(Severity, &str)> {
+    match action {
+      Action::Info(message) => Some((Severity::Info, message)),
+      Action::Warning(message) => Some((Severity::Warning, message)),
+      Action::Error(message) => Some((Severity::Error, message)),
+      _ => None,
+    }
+  }
+
+  /// Name of the style notifications of this severity are shown in.
+  pub fn style_name(self) -> &'static str {
+    match self {
+      Severity::Info => "info",
+      Severity::Warning => "warning",
+      Severity::Error => "error",
+    }
+  }
+
+  pub fn title(self) -> &'static str {
+    match self {
+      Severity::Info => "Info",
+      Severity::Warning => "Warning",
+      Severity::Error => "Error",
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+  pub severity: Severity,
+  pub message: String,
+  pub at: Instant,
+}
+
+/// Shows `Info`, `Warning` and `Error` actions as toasts in the top right corner, each for as long as `notifications`
+/// in the config says, and keeps the warnings and errors for the error log toggled by `ToggleErrorLog`.
+pub struct Notifications {
+  pub config: Config,
+  pub theme: String,
+  /// Toasts on screen, oldest first.
+  pub toasts: VecDeque<Notification>,
+  /// Warnings and errors so far, oldest first.
+  pub log: VecDeque<Notification>,
+  pub show_log: bool,
+  pub started: Instant,
+}
+
+impl Default for Notifications {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Notifications {
+  pub fn new() -> Self {
+    Self {
+      config: Config::default(),
+      theme: String::new(),
+      toasts: VecDeque::new(),
+      log: VecDeque::new(),
+      show_log: false,
+      started: Instant::now(),
+    }
+  }
+
+  pub fn notify(&mut self, severity: Severity, message: &str) {
+    match severity {
+      Severity::Info => log::info!("{message}"),
+      Severity::Warning => log::warn!("{message}"),
+      Severity::Error => log::error!("{message}"),
+    }
+    let notification = Notification { severity, message: message.to_string(), at: Instant::now() };
+    if severity > Severity::Info {
+      self.log.push_back(notification.clone());
+      if self.log.len() > LOG_LIMIT {
+        self.log.pop_front();
+      }
+    }
+    self.toasts.push_back(notification);
+    if self.toasts.len() > MAX_TOASTS {
+      self.toasts.pop_front();
+    }
+  }
+
+  /// Removes the toasts that have been shown long enough. Returns whether there were any.
+  pub fn expire(&mut self) -> bool {
+    let now = Instant::now();
+    let shown = self.toasts.len();
+    let config = &self.config.notifications;
+    self.toasts.retain(|toast| timeout(config, toast.severity).is_none_or(|timeout| now - toast.at < timeout));
+    self.toasts.len() != shown
+  }
+
+  /// Opening the error log also dismisses the toasts, as they are all in there.
+  pub fn toggle_log(&mut self) {
+    self.show_log = !self.show_log;
+    if self.show_log {
+      self.toasts.clear();
+    }
+  }
+
+  pub fn style(&self, name: &str) -> Style {
+    self.config.style(Mode::Reader, &self.theme, name)
+  }
+
+  fn draw_toasts(&self, f: &mut Frame<'_>, rect: Rect) {
+    let width = TOAST_WIDTH.min(rect.width.saturating_sub(4));
+    if width < 10 {
+      return;
+    }
+    // Below the title of the reader.
+    let mut y = rect.y + 1;
+    for toast in &self.toasts {
+      let height = rows(&toast.message, usize::from(width - 2)).min(TOAST_LINES) as u16 + 2;
+      if y + height > rect.bottom() {
+        break;
+      }
+      let area = Rect::new(rect.right() - width - 2, y, width, height);
+      let style = self.style(toast.severity.style_name());
+      let block = Block::default()
+        .title(Span::styled(toast.severity.title(), style))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(style);
+      f.render_widget(Clear, area);
+      f.render_widget(Paragraph::new(toast.message.as_str()).wrap(Wrap { trim: true }).block(block), area);
+      y += height;
+    }
+  }
+
+  fn draw_log(&self, f: &mut Frame<'_>, rect: Rect) {
+    let area = rect.inner(&Margin { horizontal: 4, vertical: 2 });
+    let block = Block::default()
+      .title(Span::styled("Warnings and Errors", Style::default().add_modifier(Modifier::BOLD)))
+      .borders(Borders::ALL)
+      .border_style(self.style("help"));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+    if self.log.is_empty() {
+      f.render_widget(Paragraph::new(Span::styled("Nothing went wrong so far", self.style("dim"))), inner);
+      return;
+    }
+    // The newest entries are at the bottom and always in view.
+    let (width, mut height) = (usize::from(inner.width), usize::from(inner.height));
+    let mut first = self.log.len();
+    while first > 0 {
+      let rows = rows(&log_line(&self.log[first - 1], self.started), width);
+      if rows > height {
+        break;
+      }
+      height -= rows;
+      first -= 1;
+    }
+    let lines = self.log.range(first..).map(|notification| {
+      let style = self.style(notification.severity.style_name());
+      Line::from(vec![
+        Span::styled(elapsed(notification.at - self.started), self.style("dim")),
+        Span::styled(format!(" {:<7} ", notification.severity.title()), style),
+        Span::raw(notification.message.as_str()),
+      ])
+    });
+    f.render_widget(Paragraph::new(lines.collect::<Vec<_>>()).wrap(Wrap { trim: false }), inner);
+  }
+}
+
+impl Component for Notifications {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.config = config;
+    Ok(())
+  }
+
+  fn theme_changed(&mut self, theme: &str) {
+    self.theme = theme.to_string();
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    if let Some((severity, message)) = Severity::of(&action) {
+      self.notify(severity, message);
+      return Ok(Some(Action::Redraw));
+    }
+    match action {
+      Action::Tick if self.expire() => Ok(Some(Action::Redraw)),
+      Action::ToggleErrorLog => {
+        self.toggle_log();
+        Ok(Some(Action::Redraw))
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    if self.show_log {
+      self.draw_log(f, rect);
+    } else {
+      self.draw_toasts(f, rect);
+    }
+    Ok(())
+  }
+}
+
+/// How long toasts of `severity` are shown, if they go away by themselves.
+fn timeout(config: &NotificationsConfig, severity: Severity) -> Option<Duration> {
+  let seconds = match severity {
+    Severity::Info => config.info,
+    Severity::Warning => config.warning,
+    Severity::Error => config.error,
+  };
+  (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+/// The line of the error log for `notification`, as plain text.
+fn log_line(notification: &Notification, started: Instant) -> String {
+  format!("{} {:<7} {}", elapsed(notification.at - started), notification.severity.title(), notification.message)
+}
+
+/// Time since start as `mm:ss`.
+fn elapsed(duration: Duration) -> String {
+  let seconds = duration.as_secs();
+  format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Rows `text` takes up when wrapped to `width` columns, at least for text without long words.
+fn rows(text: &str, width: usize) -> usize {
+  text.lines().map(|line| line.width().max(1).div_ceil(width.max(1))).sum::<usize>().max(1)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn messages(notifications: &VecDeque<Notification>) -> Vec<&str> {
+    notifications.iter().map(|notification| notification.message.as_str()).collect()
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_toasts_expire() {
+    let mut notifications = Notifications::new();
+    notifications.config.notifications.error = 0;
+    notifications.update(Action::Info("Reloaded configuration".into())).unwrap();
+    notifications.update(Action::Warning("No hyphenation patterns for `xx`".into())).unwrap();
+    notifications.update(Action::Error("Failed to save reading state".into())).unwrap();
+    assert_eq!(notifications.update(Action::Tick).unwrap(), None);
+
+    tokio::time::advance(Duration::from_secs(3)).await;
+    assert_eq!(notifications.update(Action::Tick).unwrap(), Some(Action::Redraw));
+    assert_eq!(messages(&notifications.toasts), ["No hyphenation patterns for `xx`", "Failed to save reading state"]);
+    tokio::time::advance(Duration::from_secs(60)).await;
+    notifications.update(Action::Tick).unwrap();
+    // Errors stay until the error log is opened, which lists all but the info.
+    assert_eq!(messages(&notifications.toasts), ["Failed to save reading state"]);
+    notifications.update(Action::ToggleErrorLog).unwrap();
+    assert!(notifications.toasts.is_empty());
+    assert_eq!(messages(&notifications.log), ["No hyphenation patterns for `xx`", "Failed to save reading state"]);
+  }
+
+  #[test]
+  fn test_limits() {
+    let mut notifications = Notifications::new();
+    for i in 0..LOG_LIMIT + 5 {
+      notifications.notify(Severity::Warning, &i.to_string());
+    }
+    assert_eq!(messages(&notifications.toasts), ["202", "203", "204"]);
+    assert_eq!(notifications.log.len(), LOG_LIMIT);
+    assert_eq!(notifications.log[0].message, "5");
+  }
+
+  #[test]
+  fn test_rows() {
+    assert_eq!(rows("", 10), 1);
+    assert_eq!(rows("0123456789", 10), 1);
+    assert_eq!(rows("0123456789a", 10), 2);
+    assert_eq!(rows("one\ntwo", 10), 2);
+  }
+}
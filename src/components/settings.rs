@@ -0,0 +1,111 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use serde_json::Value as JsonValue;
+
+use super::{Component, Frame};
+use crate::{action::Action, app::Mode, config::Config};
+
+/// Sections of the config listed, keybindings and styles being left to the help and the themes.
+const SECTIONS: [&str; 4] = ["reading", "auto_pause", "big_font", "notifications"];
+
+/// The settings in effect, one `section.key = value` per row, shown while `Mode::Settings` is open. They are changed
+/// in the config files, which are reloaded as they are saved.
+#[derive(Default)]
+pub struct Settings {
+  pub config: Config,
+  pub theme: String,
+  pub rows: Vec<(String, String)>,
+  pub scroll: usize,
+}
+
+impl Settings {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn style(&self, name: &str) -> Style {
+    self.config.style(Mode::Settings, &self.theme, name)
+  }
+}
+
+impl Component for Settings {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    let value = config.to_value()?;
+    self.rows.clear();
+    for section in SECTIONS {
+      flatten(section, &value[section], &mut self.rows);
+    }
+    self.scroll = self.scroll.min(self.rows.len().saturating_sub(1));
+    self.config = config;
+    Ok(())
+  }
+
+  fn theme_changed(&mut self, theme: &str) {
+    self.theme = theme.to_string();
+  }
+
+  fn modes(&self) -> &'static [Mode] {
+    &[Mode::Settings]
+  }
+
+  fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    match key.code {
+      KeyCode::Char('j') | KeyCode::Down => self.scroll = (self.scroll + 1).min(self.rows.len().saturating_sub(1)),
+      KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+      _ => return Ok(None),
+    }
+    Ok(Some(Action::Redraw))
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    let block = Block::default()
+      .title("Settings")
+      .title_alignment(Alignment::Center)
+      .borders(Borders::ALL)
+      .border_style(self.style("border"))
+      .border_type(BorderType::Rounded);
+    let inner = block.inner(rect).inner(&Margin { horizontal: 1, vertical: 0 });
+    f.render_widget(Clear, rect);
+    f.render_widget(block, rect);
+    let rects = Layout::default().constraints([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+    // The config directory is listed by `--version`.
+    let hint = "Change these in a config file, it is reloaded when saved";
+    f.render_widget(Paragraph::new(Span::styled(hint, self.style("dim"))).alignment(Alignment::Center), rects[1]);
+    let width = self.rows.iter().map(|(name, _)| name.len()).max().unwrap_or_default();
+    let lines = self.rows.iter().skip(self.scroll).map(|(name, value)| {
+      Line::from(vec![Span::styled(format!("{name:<width$}  "), self.style("key")), Span::raw(value.as_str())])
+    });
+    f.render_widget(Paragraph::new(lines.collect::<Vec<_>>()).style(self.style("text")), rects[0]);
+    Ok(())
+  }
+}
+
+/// Appends the leaves of `value` as rows named by their path, e.g. `reading.pauses.comma`.
+fn flatten(path: &str, value: &JsonValue, rows: &mut Vec<(String, String)>) {
+  match value {
+    JsonValue::Object(map) => {
+      for (key, value) in map {
+        flatten(&format!("{path}.{key}"), value, rows);
+      }
+    },
+    JsonValue::String(s) => rows.push((path.to_string(), s.clone())),
+    value => rows.push((path.to_string(), value.to_string())),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_flatten() {
+    let mut rows = vec![];
+    flatten("reading", &json!({"pauses": {"comma": 1.5}, "theme": "dark", "library": ["./assets"]}), &mut rows);
+    let rows: Vec<_> = rows.iter().map(|(name, value)| format!("{name} = {value}")).collect();
+    assert_eq!(rows, ["reading.library = [\"./assets\"]", "reading.pauses.comma = 1.5", "reading.theme = dark"]);
+  }
+}
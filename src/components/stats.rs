@@ -0,0 +1,150 @@
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::{
+  sync::broadcast::{self, error::TryRecvError},
+  time::Duration,
+};
+
+use super::{Component, Frame};
+use crate::{action::Action, app::Mode, config::Config, remote::Event};
+
+/// Words on screen for longer than this were left there for a break, which does not count as time spent reading.
+const IDLE: Duration = Duration::from_secs(5);
+
+/// Reading so far this session, counted from the events the reader reports to remote control clients and shown
+/// while `Mode::Stats` is open.
+pub struct Stats {
+  pub config: Config,
+  pub theme: String,
+  pub events: broadcast::Receiver<Event>,
+  pub words: usize,
+  pub reading: Duration,
+  pub chapter: Option<String>,
+  pub finished: usize,
+}
+
+impl Stats {
+  pub fn new(events: broadcast::Receiver<Event>) -> Self {
+    Self {
+      config: Config::default(),
+      theme: String::new(),
+      events,
+      words: 0,
+      reading: Duration::ZERO,
+      chapter: None,
+      finished: 0,
+    }
+  }
+
+  /// Counts the events reported since the last call.
+  pub fn receive(&mut self) {
+    loop {
+      match self.events.try_recv() {
+        Ok(Event::Read { words, ms }) => {
+          self.words += words;
+          let on_screen = Duration::from_millis(ms);
+          if on_screen < IDLE {
+            self.reading += on_screen;
+          }
+        },
+        // Words are also shown when moving around the text, which is not reading them.
+        Ok(Event::Word { .. }) => {},
+        Ok(Event::Chapter { title, .. }) => self.chapter = Some(title),
+        Ok(Event::Finished) => self.finished += 1,
+        Ok(Event::Error { .. }) => {},
+        // Events are received every tick, far more often than words are read, so few could be missed this way and
+        // there is no telling how many words they were.
+        Err(TryRecvError::Lagged(missed)) => log::warn!("Reading stats missed {missed} events"),
+        Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+      }
+    }
+  }
+
+  /// Words per minute over the time spent reading.
+  pub fn wpm(&self) -> Option<f64> {
+    let minutes = self.reading.as_secs_f64() / 60.0;
+    (minutes > 0.0).then(|| self.words as f64 / minutes)
+  }
+
+  pub fn style(&self, name: &str) -> Style {
+    self.config.style(Mode::Stats, &self.theme, name)
+  }
+}
+
+impl Component for Stats {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.config = config;
+    Ok(())
+  }
+
+  fn theme_changed(&mut self, theme: &str) {
+    self.theme = theme.to_string();
+  }
+
+  fn modes(&self) -> &'static [Mode] {
+    &[Mode::Stats]
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    if action == Action::Tick {
+      self.receive();
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
+    self.receive();
+    let block = Block::default()
+      .title("Reading Stats")
+      .title_alignment(Alignment::Center)
+      .borders(Borders::ALL)
+      .border_style(self.style("border"))
+      .border_type(BorderType::Rounded);
+    let inner = block.inner(rect).inner(&Margin { horizontal: 2, vertical: 1 });
+    f.render_widget(Clear, rect);
+    f.render_widget(block, rect);
+    let seconds = self.reading.as_secs();
+    let rows = [
+      ("Words read", self.words.to_string()),
+      ("Time reading", format!("{}:{:02}", seconds / 60, seconds % 60)),
+      ("Speed", self.wpm().map_or("-".to_string(), |wpm| format!("{wpm:.0} wpm"))),
+      ("Chapter", self.chapter.clone().unwrap_or_else(|| "-".to_string())),
+      ("Books finished", self.finished.to_string()),
+    ];
+    let lines = rows
+      .into_iter()
+      .map(|(name, value)| Line::from(vec![Span::styled(format!("{name:<16} "), self.style("dim")), value.into()]));
+    f.render_widget(Paragraph::new(lines.collect::<Vec<_>>()).style(self.style("text")), inner);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn test_receive() {
+    let (events, receiver) = broadcast::channel(16);
+    let mut stats = Stats::new(receiver);
+    for _ in 0..3 {
+      events.send(Event::Read { words: 1, ms: 200 }).unwrap();
+    }
+    stats.receive();
+    // A break is not reading time.
+    events.send(Event::Read { words: 1, ms: 60_000 }).unwrap();
+    events.send(Event::Chapter { index: 1, position: 0, title: "Down the Rabbit-Hole".into() }).unwrap();
+    stats.receive();
+    assert_eq!((stats.words, stats.reading), (4, Duration::from_millis(600)));
+    assert_eq!(stats.wpm(), Some(400.0));
+    assert_eq!(stats.chapter.as_deref(), Some("Down the Rabbit-Hole"));
+
+    // Chunks count all their words, and words shown while moving around none.
+    events.send(Event::Word { index: 20, word: "Alice".into(), wpm: 300 }).unwrap();
+    events.send(Event::Read { words: 3, ms: 600 }).unwrap();
+    stats.receive();
+    assert_eq!((stats.words, stats.reading), (7, Duration::from_millis(1200)));
+  }
+}
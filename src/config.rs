@@ -1,4 +1,6 @@
-use std::{collections::HashMap, fmt, path::PathBuf};
+pub mod check;
+
+use std::{collections::HashMap, fmt, path::PathBuf, str::FromStr, time::SystemTime};
 
 use color_eyre::eyre::Result;
 use config::Value;
@@ -9,12 +11,20 @@ use serde::{
   de::{self, Deserializer, MapAccess, Visitor},
   Deserialize, Serialize,
 };
-use serde_json::Value as JsonValue;
+use serde_json::{json, Map, Value as JsonValue};
 
 use crate::{action::Action, app::Mode};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
+const CONFIG_FILES: [(&str, config::FileFormat); 5] = [
+  ("config.json5", config::FileFormat::Json5),
+  ("config.json", config::FileFormat::Json),
+  ("config.yaml", config::FileFormat::Yaml),
+  ("config.toml", config::FileFormat::Toml),
+  ("config.ini", config::FileFormat::Ini),
+];
+
 #[derive(Clone, Debug, Deserialize, Default)]
 pub struct AppConfig {
   #[serde(default)]
@@ -23,6 +33,321 @@ pub struct AppConfig {
   pub _config_dir: PathBuf,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoPauseConfig {
+  pub on_focus_lost: bool,
+  pub on_suspend: bool,
+  pub on_resize: bool,
+  pub rewind_words: usize,
+  pub resume: bool,
+  pub resume_countdown: u64,
+}
+
+impl Default for AutoPauseConfig {
+  fn default() -> Self {
+    Self {
+      on_focus_lost: true,
+      on_suspend: true,
+      on_resize: true,
+      rewind_words: 3,
+      resume: true,
+      resume_countdown: 3,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BigFontConfig {
+  pub enabled: bool,
+  pub font: String,
+  pub max_scale: usize,
+}
+
+impl Default for BigFontConfig {
+  fn default() -> Self {
+    Self { enabled: false, font: "block".to_string(), max_scale: 4 }
+  }
+}
+
+/// Seconds a notification of each severity stays on screen, 0 keeping it until the error log is opened.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+  pub info: u64,
+  pub warning: u64,
+  pub error: u64,
+}
+
+impl Default for NotificationsConfig {
+  fn default() -> Self {
+    Self { info: 3, warning: 6, error: 10 }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PauseConfig {
+  pub comma: f64,
+  pub sentence: f64,
+  pub paragraph: f64,
+  pub long_word: f64,
+  pub long_word_length: usize,
+  pub line_break: f64,
+  pub stanza_break: f64,
+  pub dash: f64,
+}
+
+impl Default for PauseConfig {
+  fn default() -> Self {
+    Self {
+      comma: 1.5,
+      sentence: 2.0,
+      paragraph: 3.0,
+      long_word: 1.3,
+      long_word_length: 8,
+      line_break: 1.0,
+      stanza_break: 2.0,
+      dash: 1.3,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HyphenationConfig {
+  pub enabled: bool,
+  pub max_length: usize,
+  pub language: String,
+}
+
+impl Default for HyphenationConfig {
+  fn default() -> Self {
+    Self { enabled: true, max_length: 12, language: "en".to_string() }
+  }
+}
+
+/// Whether line breaks in the text are kept, see `text::tokenize`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoetryMode {
+  #[default]
+  Auto,
+  On,
+  Off,
+}
+
+impl FromStr for PoetryMode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "auto" => Ok(PoetryMode::Auto),
+      "on" => Ok(PoetryMode::On),
+      "off" => Ok(PoetryMode::Off),
+      _ => Err("expected one of: auto, on, off".to_string()),
+    }
+  }
+}
+
+impl fmt::Display for PoetryMode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PoetryMode::Auto => write!(f, "auto"),
+      PoetryMode::On => write!(f, "on"),
+      PoetryMode::Off => write!(f, "off"),
+    }
+  }
+}
+
+/// Words ending in a full stop that do not end a sentence, as listed in the default config.
+const ABBREVIATIONS: &[&str] = &[
+  "Mr.", "Mrs.", "Ms.", "Dr.", "St.", "Jr.", "Sr.", "Prof.", "Rev.", "Gen.", "Capt.", "Col.", "Lt.", "Mt.", "No.",
+  "vs.", "etc.", "e.g.", "i.e.", "cf.", "viz.", "a.m.", "p.m.", "Jan.", "Feb.", "Aug.", "Sept.", "Oct.", "Nov.", "Dec.",
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadingConfig {
+  pub wpm: u32,
+  pub chunk_size: usize,
+  pub pauses: PauseConfig,
+  pub abbreviations: Vec<String>,
+  pub orp: bool,
+  pub hyphenation: HyphenationConfig,
+  pub poetry: PoetryMode,
+  pub context: bool,
+  pub theme: String,
+  pub library: Vec<PathBuf>,
+  pub auto_resume: bool,
+  pub start_countdown: u64,
+}
+
+impl Default for ReadingConfig {
+  fn default() -> Self {
+    Self {
+      wpm: 300,
+      chunk_size: 1,
+      pauses: PauseConfig::default(),
+      abbreviations: ABBREVIATIONS.iter().map(|a| a.to_string()).collect(),
+      orp: true,
+      hyphenation: HyphenationConfig::default(),
+      poetry: PoetryMode::Auto,
+      context: false,
+      theme: "dark".to_string(),
+      library: vec![PathBuf::from("./assets")],
+      auto_resume: true,
+      start_countdown: 0,
+    }
+  }
+}
+
+/// Output formats for `config dump`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DumpFormat {
+  #[default]
+  Json5,
+  Toml,
+  Yaml,
+}
+
+/// Reading settings a book can override, stored with its reading state. Unset fields fall back to `reading`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadingOverrides {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub wpm: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub chunk_size: Option<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub theme: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub comma: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub sentence: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub paragraph: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub long_word: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub long_word_length: Option<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub line_break: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub stanza_break: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub dash: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub poetry: Option<PoetryMode>,
+}
+
+const OVERRIDE_KEYS: &[&str] = &[
+  "wpm",
+  "chunk_size",
+  "theme",
+  "pauses.comma",
+  "pauses.sentence",
+  "pauses.paragraph",
+  "pauses.long_word",
+  "pauses.long_word_length",
+  "pauses.line_break",
+  "pauses.stanza_break",
+  "pauses.dash",
+  "poetry",
+];
+
+impl ReadingOverrides {
+  pub fn is_empty(&self) -> bool {
+    self == &Self::default()
+  }
+
+  pub fn apply(&self, reading: &mut ReadingConfig) {
+    let pauses = &mut reading.pauses;
+    reading.wpm = self.wpm.unwrap_or(reading.wpm);
+    reading.chunk_size = self.chunk_size.unwrap_or(reading.chunk_size);
+    reading.theme = self.theme.clone().unwrap_or_else(|| reading.theme.clone());
+    pauses.comma = self.comma.unwrap_or(pauses.comma);
+    pauses.sentence = self.sentence.unwrap_or(pauses.sentence);
+    pauses.paragraph = self.paragraph.unwrap_or(pauses.paragraph);
+    pauses.long_word = self.long_word.unwrap_or(pauses.long_word);
+    pauses.long_word_length = self.long_word_length.unwrap_or(pauses.long_word_length);
+    pauses.line_break = self.line_break.unwrap_or(pauses.line_break);
+    pauses.stanza_break = self.stanza_break.unwrap_or(pauses.stanza_break);
+    pauses.dash = self.dash.unwrap_or(pauses.dash);
+    reading.poetry = self.poetry.unwrap_or(reading.poetry);
+  }
+
+  /// Sets one setting by its key in the `reading` section, e.g. `wpm` or `pauses.comma`.
+  pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+    fn parse<T: FromStr + PartialOrd + Default>(key: &str, value: &str) -> Result<Option<T>, String>
+    where
+      T::Err: fmt::Display,
+    {
+      match value.parse() {
+        Ok(value) if value > T::default() => Ok(Some(value)),
+        Ok(_) => Err(format!("`{key}` must be greater than zero")),
+        Err(e) => Err(format!("Invalid value `{value}` for `{key}`: {e}")),
+      }
+    }
+    match key {
+      "wpm" => self.wpm = parse(key, value)?,
+      "chunk_size" => self.chunk_size = parse(key, value)?,
+      "theme" => {
+        check_theme(value)?;
+        self.theme = Some(value.to_string());
+      },
+      "pauses.comma" => self.comma = parse(key, value)?,
+      "pauses.sentence" => self.sentence = parse(key, value)?,
+      "pauses.paragraph" => self.paragraph = parse(key, value)?,
+      "pauses.long_word" => self.long_word = parse(key, value)?,
+      "pauses.long_word_length" => self.long_word_length = parse(key, value)?,
+      "pauses.line_break" => self.line_break = parse(key, value)?,
+      "pauses.stanza_break" => self.stanza_break = parse(key, value)?,
+      "pauses.dash" => self.dash = parse(key, value)?,
+      "poetry" => self.poetry = Some(value.parse().map_err(|e| format!("Invalid value `{value}` for `{key}`: {e}"))?),
+      _ => return Err(format!("Unknown setting `{key}`, expected one of: {}", OVERRIDE_KEYS.join(", "))),
+    }
+    Ok(())
+  }
+}
+
+/// Parses space separated `key=value` pairs, as shown by `Display`.
+impl FromStr for ReadingOverrides {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut overrides = Self::default();
+    for setting in s.split_whitespace() {
+      let (key, value) = setting.split_once('=').ok_or_else(|| format!("Expected `key=value`, got `{setting}`"))?;
+      overrides.set(key, value)?;
+    }
+    Ok(overrides)
+  }
+}
+
+impl fmt::Display for ReadingOverrides {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let values = [
+      self.wpm.map(|v| v.to_string()),
+      self.chunk_size.map(|v| v.to_string()),
+      self.theme.clone(),
+      self.comma.map(|v| v.to_string()),
+      self.sentence.map(|v| v.to_string()),
+      self.paragraph.map(|v| v.to_string()),
+      self.long_word.map(|v| v.to_string()),
+      self.long_word_length.map(|v| v.to_string()),
+      self.line_break.map(|v| v.to_string()),
+      self.stanza_break.map(|v| v.to_string()),
+      self.dash.map(|v| v.to_string()),
+      self.poetry.map(|v| v.to_string()),
+    ];
+    let settings: Vec<String> =
+      OVERRIDE_KEYS.iter().zip(values).filter_map(|(key, value)| Some(format!("{key}={}", value?))).collect();
+    write!(f, "{}", settings.join(" "))
+  }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Config {
   #[serde(default, flatten)]
@@ -31,26 +356,33 @@ pub struct Config {
   pub keybindings: KeyBindings,
   #[serde(default)]
   pub styles: Styles,
+  #[serde(default)]
+  pub auto_pause: AutoPauseConfig,
+  #[serde(default)]
+  pub big_font: BigFontConfig,
+  #[serde(default)]
+  pub notifications: NotificationsConfig,
+  #[serde(default)]
+  pub reading: ReadingConfig,
 }
 
 impl Config {
+  /// Resolves a named UI style, letting `styles` entries for `mode` override the built-in `theme`.
+  pub fn style(&self, mode: Mode, theme: &str, name: &str) -> Style {
+    self.styles.get(&mode).and_then(|styles| styles.get(name)).copied().unwrap_or_else(|| theme_style(theme, name))
+  }
+
   pub fn new() -> Result<Self, config::ConfigError> {
     let default_config: Config = json5::from_str(CONFIG).unwrap();
     let data_dir = crate::utils::get_data_dir();
     let config_dir = crate::utils::get_config_dir();
     let mut builder = config::Config::builder()
       .set_default("_data_dir", data_dir.to_str().unwrap())?
-      .set_default("_config_dir", config_dir.to_str().unwrap())?;
-
-    let config_files = [
-      ("config.json5", config::FileFormat::Json5),
-      ("config.json", config::FileFormat::Json),
-      ("config.yaml", config::FileFormat::Yaml),
-      ("config.toml", config::FileFormat::Toml),
-      ("config.ini", config::FileFormat::Ini),
-    ];
+      .set_default("_config_dir", config_dir.to_str().unwrap())?
+      .set_default("reading", reading_defaults()?)?;
+
     let mut found_config = false;
-    for (file, format) in &config_files {
+    for (file, format) in &CONFIG_FILES {
       builder = builder.add_source(config::File::from(config_dir.join(file)).format(*format).required(false));
       if config_dir.join(file).exists() {
         found_config = true
@@ -71,12 +403,74 @@ impl Config {
     for (mode, default_styles) in default_config.styles.iter() {
       let user_styles = cfg.styles.entry(*mode).or_default();
       for (style_key, style) in default_styles.iter() {
-        user_styles.entry(style_key.clone()).or_insert_with(|| style.clone());
+        user_styles.entry(style_key.clone()).or_insert(*style);
       }
     }
 
     Ok(cfg)
   }
+
+  /// The effective configuration in the same shape as the config file, with keys sorted.
+  pub fn to_value(&self) -> Result<JsonValue> {
+    let keybindings: Map<String, JsonValue> = self
+      .keybindings
+      .iter()
+      .map(|(mode, bindings)| {
+        let bindings = bindings.iter().map(|(keys, action)| (key_sequence_to_string(keys), json!(action.to_string())));
+        Ok((serde_json::to_value(mode)?.as_str().unwrap_or_default().to_string(), bindings.collect()))
+      })
+      .collect::<Result<_>>()?;
+    let styles: Map<String, JsonValue> = self
+      .styles
+      .iter()
+      .map(|(mode, styles)| {
+        let styles = styles.iter().map(|(name, style)| (name.clone(), json!(style_to_string(style))));
+        Ok((serde_json::to_value(mode)?.as_str().unwrap_or_default().to_string(), styles.collect()))
+      })
+      .collect::<Result<_>>()?;
+    Ok(json!({
+      "keybindings": keybindings,
+      "styles": styles,
+      "reading": self.reading,
+      "big_font": self.big_font,
+      "auto_pause": self.auto_pause,
+      "notifications": self.notifications,
+    }))
+  }
+
+  /// Serializes the effective configuration so it can be used as a config file.
+  pub fn dump(&self, format: DumpFormat) -> Result<String> {
+    let value = self.to_value()?;
+    Ok(match format {
+      DumpFormat::Json5 => serde_json::to_string_pretty(&value)?,
+      DumpFormat::Toml => toml::to_string_pretty(&value)?,
+      DumpFormat::Yaml => serde_yaml::to_string(&value)?,
+    })
+  }
+
+  /// Config files present in the config directory, in the order they are merged.
+  pub fn files() -> Vec<PathBuf> {
+    let config_dir = crate::utils::get_config_dir();
+    CONFIG_FILES.iter().map(|(file, _)| config_dir.join(file)).filter(|path| path.exists()).collect()
+  }
+
+  /// Modification times of every config file, used to detect when the config needs reloading.
+  pub fn modified() -> Vec<Option<SystemTime>> {
+    let config_dir = crate::utils::get_config_dir();
+    CONFIG_FILES
+      .iter()
+      .map(|(file, _)| std::fs::metadata(config_dir.join(file)).and_then(|m| m.modified()).ok())
+      .collect()
+  }
+}
+
+// Unlike keybindings and styles, which are merged after deserializing, `reading` is seeded as a default
+// table so that a user file only needs to set the fields it changes.
+fn reading_defaults() -> Result<Value, config::ConfigError> {
+  config::Config::builder()
+    .add_source(config::File::from_str(CONFIG, config::FileFormat::Json5))
+    .build()?
+    .get::<Value>("reading")
 }
 
 #[derive(Clone, Debug, Default, Deref, DerefMut)]
@@ -92,11 +486,13 @@ impl<'de> Deserialize<'de> for KeyBindings {
     let keybindings = parsed_map
       .into_iter()
       .map(|(mode, inner_map)| {
-        let converted_inner_map =
-          inner_map.into_iter().map(|(key_str, cmd)| (parse_key_sequence(&key_str).unwrap(), cmd)).collect();
-        (mode, converted_inner_map)
+        let converted_inner_map = inner_map
+          .into_iter()
+          .map(|(key_str, cmd)| Ok((parse_key_sequence(&key_str).map_err(de::Error::custom)?, cmd)))
+          .collect::<Result<_, D::Error>>()?;
+        Ok((mode, converted_inner_map))
       })
-      .collect();
+      .collect::<Result<_, D::Error>>()?;
 
     Ok(KeyBindings(keybindings))
   }
@@ -198,10 +594,10 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     KeyCode::Delete => "delete",
     KeyCode::Insert => "insert",
     KeyCode::F(c) => {
-      char = format!("f({c})");
+      char = format!("f{c}");
       &char
     },
-    KeyCode::Char(c) if c == ' ' => "space",
+    KeyCode::Char(' ') => "space",
     KeyCode::Char(c) => {
       char = c.to_string();
       &char
@@ -270,6 +666,11 @@ pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
   sequences.into_iter().map(parse_key_event).collect()
 }
 
+/// Formats keys the way `parse_key_sequence` reads them, e.g. `<ctrl-d>` or `<g><g>`.
+pub fn key_sequence_to_string(keys: &[KeyEvent]) -> String {
+  keys.iter().map(|key| format!("<{}>", key_event_to_string(key))).collect()
+}
+
 #[derive(Clone, Debug, Default, Deref, DerefMut)]
 pub struct Styles(pub HashMap<Mode, HashMap<String, Style>>);
 
@@ -292,6 +693,97 @@ impl<'de> Deserialize<'de> for Styles {
   }
 }
 
+// Built-in themes, written in the same syntax as the `styles` section of the config file.
+pub const THEMES: &[(&str, &[(&str, &str)])] = &[
+  ("dark", &[
+    ("text", "cyan"),
+    ("word", "bold white"),
+    ("pivot", "bold red"),
+    ("progress", "cyan"),
+    ("border", ""),
+    ("border_processing", "yellow"),
+    ("key", "red"),
+    ("highlight", "yellow"),
+    ("input", "yellow"),
+    ("help", "yellow"),
+    ("status", "bold"),
+    ("dim", "gray10"),
+    ("info", "cyan"),
+    ("warning", "yellow"),
+    ("error", "bold red"),
+  ]),
+  ("light", &[
+    ("text", "blue"),
+    ("word", "bold black"),
+    ("pivot", "bold red"),
+    ("progress", "blue"),
+    ("border", "black"),
+    ("border_processing", "magenta"),
+    ("key", "red"),
+    ("highlight", "magenta"),
+    ("input", "magenta"),
+    ("help", "magenta"),
+    ("status", "bold black"),
+    ("dim", "gray12"),
+    ("info", "blue"),
+    ("warning", "magenta"),
+    ("error", "bold red"),
+  ]),
+  ("high-contrast", &[
+    ("text", "white on black"),
+    ("word", "bold white on black"),
+    ("pivot", "bold yellow on black"),
+    ("progress", "bold yellow"),
+    ("border", "white"),
+    ("border_processing", "bold yellow"),
+    ("key", "bold yellow"),
+    ("highlight", "bold white"),
+    ("input", "bold yellow"),
+    ("help", "bold yellow"),
+    ("status", "bold white"),
+    ("dim", "white"),
+    ("info", "bold white"),
+    ("warning", "bold yellow"),
+    ("error", "bold red"),
+  ]),
+  ("solarized", &[
+    ("text", "rgb233 on rgb012"),
+    ("word", "bold rgb344 on rgb012"),
+    ("pivot", "bold rgb410 on rgb012"),
+    ("progress", "rgb024"),
+    ("border", "rgb122"),
+    ("border_processing", "rgb330"),
+    ("key", "rgb411"),
+    ("highlight", "rgb330"),
+    ("input", "rgb330"),
+    ("help", "rgb330"),
+    ("status", "bold rgb233"),
+    ("dim", "rgb122"),
+    ("info", "rgb024"),
+    ("warning", "rgb330"),
+    ("error", "rgb411"),
+  ]),
+];
+
+pub fn theme_style(theme: &str, name: &str) -> Style {
+  let (_, styles) = THEMES.iter().find(|(t, _)| *t == theme).unwrap_or(&THEMES[0]);
+  styles.iter().find(|(n, _)| *n == name).map(|(_, style)| parse_style(style)).unwrap_or_default()
+}
+
+/// Checks that `theme` is one of `THEMES`, listing them when it is not.
+pub fn check_theme(theme: &str) -> Result<(), String> {
+  if THEMES.iter().any(|(name, _)| *name == theme) {
+    return Ok(());
+  }
+  let themes = THEMES.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ");
+  Err(format!("Unknown theme `{theme}`, expected one of: {themes}"))
+}
+
+pub fn next_theme(theme: &str) -> &'static str {
+  let i = THEMES.iter().position(|(t, _)| *t == theme).map_or(0, |i| i + 1);
+  THEMES[i % THEMES.len()].0
+}
+
 pub fn parse_style(line: &str) -> Style {
   let (foreground, background) = line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
   let foreground = process_color_string(foreground);
@@ -308,6 +800,51 @@ pub fn parse_style(line: &str) -> Style {
   style
 }
 
+/// Formats a style parsed by `parse_style` back into the same syntax.
+pub fn style_to_string(style: &Style) -> String {
+  let color = |color: Option<Color>| match color {
+    Some(Color::Indexed(c)) => format!("color{c}"),
+    _ => String::new(),
+  };
+  let mut parts = Vec::new();
+  let modifiers = [(Modifier::BOLD, "bold"), (Modifier::UNDERLINED, "underline"), (Modifier::REVERSED, "inverse")];
+  for (modifier, name) in modifiers {
+    if style.add_modifier.contains(modifier) {
+      parts.push(name.to_string());
+    }
+  }
+  parts.push(color(style.fg));
+  if style.bg.is_some() {
+    parts.push(format!("on {}", color(style.bg)));
+  }
+  parts.retain(|part| !part.is_empty());
+  parts.join(" ")
+}
+
+/// Checks that every color in a style string is one `parse_style` understands, instead of being silently dropped.
+pub fn validate_style(line: &str) -> Result<(), String> {
+  let (foreground, background) = line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
+  for part in [foreground.to_string(), background.replace("on ", "")] {
+    let (color, _) = process_color_string(&part);
+    let color = color.trim();
+    let valid = if color.is_empty() {
+      true
+    } else if let Some(n) = color.strip_prefix("color") {
+      n.parse::<u8>().is_ok()
+    } else if let Some(n) = color.strip_prefix("gray") {
+      n.parse::<u8>().is_ok_and(|n| n < 24)
+    } else if let Some(n) = color.strip_prefix("rgb") {
+      n.len() == 3 && n.chars().all(|c| ('0'..='5').contains(&c))
+    } else {
+      parse_color(color).is_some()
+    };
+    if !valid {
+      return Err(format!("Unknown color `{color}` in style `{line}`"));
+    }
+  }
+  Ok(())
+}
+
 fn process_color_string(color_str: &str) -> (String, Modifier) {
   let color = color_str
     .replace("grey", "gray")
@@ -344,9 +881,8 @@ fn parse_color(s: &str) -> Option<Color> {
     let c = 232 + s.trim_start_matches("gray").parse::<u8>().unwrap_or_default();
     Some(Color::Indexed(c))
   } else if s.contains("rgb") {
-    let red = (s.as_bytes()[3] as char).to_digit(10).unwrap_or_default() as u8;
-    let green = (s.as_bytes()[4] as char).to_digit(10).unwrap_or_default() as u8;
-    let blue = (s.as_bytes()[5] as char).to_digit(10).unwrap_or_default() as u8;
+    let digit = |i: usize| s.as_bytes().get(i).and_then(|&b| (b as char).to_digit(10)).unwrap_or_default() as u8;
+    let (red, green, blue) = (digit(3), digit(4), digit(5));
     let c = 16 + red * 36 + green * 6 + blue;
     Some(Color::Indexed(c))
   } else if s == "bold black" {
@@ -429,7 +965,7 @@ mod tests {
   #[test]
   fn test_parse_color_rgb() {
     let color = parse_color("rgb123");
-    let expected = 16 + 1 * 36 + 2 * 6 + 3;
+    let expected = 16 + 36 + 2 * 6 + 3;
     assert_eq!(color, Some(Color::Indexed(expected)));
   }
 
@@ -439,16 +975,142 @@ mod tests {
     assert_eq!(color, None);
   }
 
+  #[test]
+  fn test_validate_style() {
+    assert_eq!(validate_style("bold red on rgb012"), Ok(()));
+    assert_eq!(validate_style("underline gray10"), Ok(()));
+    assert_eq!(validate_style(""), Ok(()));
+    assert!(validate_style("purple").is_err());
+    assert!(validate_style("red on rgb9").is_err());
+    assert!(validate_style("color300").is_err());
+  }
+
+  #[test]
+  fn test_style_to_string_round_trip() {
+    for line in ["", "red", "bold white on black", "underline inverse rgb123 on gray5", "on blue"] {
+      let style = parse_style(line);
+      assert_eq!(parse_style(&style_to_string(&style)), style, "{line}");
+    }
+  }
+
+  #[test]
+  fn test_dump_round_trip() -> Result<()> {
+    let c = Config::new()?;
+    for format in [DumpFormat::Json5, DumpFormat::Toml, DumpFormat::Yaml] {
+      let file_format = match format {
+        DumpFormat::Json5 => config::FileFormat::Json5,
+        DumpFormat::Toml => config::FileFormat::Toml,
+        DumpFormat::Yaml => config::FileFormat::Yaml,
+      };
+      let source = config::File::from_str(&c.dump(format)?, file_format);
+      let dumped: Config = config::Config::builder().add_source(source).build()?.try_deserialize()?;
+      assert_eq!(dumped.keybindings.0, c.keybindings.0);
+      assert_eq!(dumped.styles.0, c.styles.0);
+      assert_eq!(dumped.reading, c.reading);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_reading_overrides() {
+    let overrides: ReadingOverrides = "wpm=180 theme=light pauses.paragraph=5 poetry=on".parse().unwrap();
+    assert_eq!(overrides.to_string(), "wpm=180 theme=light pauses.paragraph=5 poetry=on");
+    let mut reading = ReadingConfig::default();
+    overrides.apply(&mut reading);
+    assert_eq!(reading.wpm, 180);
+    assert_eq!(reading.theme, "light");
+    assert_eq!(reading.pauses.paragraph, 5.0);
+    assert_eq!(reading.poetry, PoetryMode::On);
+    assert_eq!(reading.pauses.comma, ReadingConfig::default().pauses.comma);
+    assert_eq!(reading.chunk_size, ReadingConfig::default().chunk_size);
+
+    assert!("".parse::<ReadingOverrides>().unwrap().is_empty());
+    assert!("wpm".parse::<ReadingOverrides>().is_err());
+    assert!("wpm=fast".parse::<ReadingOverrides>().is_err());
+    assert!("chunk_size=0".parse::<ReadingOverrides>().is_err());
+    assert!("theme=neon".parse::<ReadingOverrides>().is_err());
+    assert!("speed=3".parse::<ReadingOverrides>().unwrap_err().contains("Unknown setting `speed`"));
+  }
+
+  #[test]
+  fn test_theme_style() {
+    assert_eq!(theme_style("dark", "pivot"), parse_style("bold red"));
+    assert_eq!(theme_style("high-contrast", "word").bg, Some(Color::Indexed(0)));
+    assert_eq!(theme_style("no-such-theme", "text"), theme_style("dark", "text"));
+    assert_eq!(theme_style("dark", "no-such-style"), Style::default());
+  }
+
+  #[test]
+  fn test_next_theme() {
+    assert_eq!(next_theme("dark"), "light");
+    assert_eq!(next_theme(THEMES[THEMES.len() - 1].0), "dark");
+    assert_eq!(next_theme("no-such-theme"), "dark");
+  }
+
+  #[test]
+  fn test_style_overrides_theme() {
+    let c: Config = json5::from_str(r#"{ "styles": { "Home": { "pivot": "green" } } }"#).unwrap();
+    assert_eq!(c.style(Mode::Reader, "light", "pivot"), parse_style("green"));
+    assert_eq!(c.style(Mode::Reader, "light", "word"), theme_style("light", "word"));
+  }
+
+  #[test]
+  fn test_reading_defaults_merge() -> Result<()> {
+    let user = r#"{ "reading": { "wpm": 500, "pauses": { "comma": 3.0 } } }"#;
+    let c: Config = config::Config::builder()
+      .set_default("reading", reading_defaults()?)?
+      .add_source(config::File::from_str(user, config::FileFormat::Json5))
+      .build()?
+      .try_deserialize()?;
+    let defaults = ReadingConfig::default();
+    assert_eq!(c.reading.wpm, 500);
+    assert_eq!(c.reading.pauses.comma, 3.0);
+    assert_eq!(c.reading.pauses.sentence, defaults.pauses.sentence);
+    assert_eq!(c.reading.theme, defaults.theme);
+    assert_eq!(c.reading.library, defaults.library);
+    Ok(())
+  }
+
   #[test]
   fn test_config() -> Result<()> {
     let c = Config::new()?;
+    assert_eq!(c.reading, ReadingConfig::default());
     assert_eq!(
-      c.keybindings.get(&Mode::Home).unwrap().get(&parse_key_sequence("<q>").unwrap_or_default()).unwrap(),
+      c.keybindings.get(&Mode::Reader).unwrap().get(&parse_key_sequence("<q>").unwrap_or_default()).unwrap(),
       &Action::Quit
     );
+    assert_eq!(
+      c.keybindings.get(&Mode::Reader).unwrap().get(&parse_key_sequence("<Shift-j>").unwrap_or_default()).unwrap(),
+      &Action::IncrementText(10)
+    );
     Ok(())
   }
 
+  #[test]
+  fn test_auto_pause_partial() {
+    let c: Config = json5::from_str(r#"{ "auto_pause": { "rewind_words": 10, "on_resize": false } }"#).unwrap();
+    assert_eq!(c.auto_pause.rewind_words, 10);
+    assert!(!c.auto_pause.on_resize);
+    assert!(c.auto_pause.on_focus_lost);
+    assert_eq!(c.auto_pause.resume_countdown, 3);
+  }
+
+  #[test]
+  fn test_reading_partial() {
+    let c: Config = json5::from_str(r#"{ "reading": { "wpm": 450, "pauses": { "comma": 2.5 } } }"#).unwrap();
+    let defaults = ReadingConfig::default();
+    assert_eq!((c.reading.wpm, c.reading.pauses.comma), (450, 2.5));
+    assert_eq!(c.reading.pauses.sentence, defaults.pauses.sentence);
+    assert_eq!(c.reading.hyphenation, defaults.hyphenation);
+    assert_eq!(c.reading.abbreviations, defaults.abbreviations);
+  }
+
+  #[test]
+  fn test_invalid_keybinding_is_an_error() {
+    let c = json5::from_str::<Config>(r#"{ "keybindings": { "Home": { "<ctrl-nope>": "Quit" } } }"#);
+    assert!(c.unwrap_err().to_string().contains("Unable to parse nope"));
+  }
+
   #[test]
   fn test_simple_keys() {
     assert_eq!(parse_key_event("a").unwrap(), KeyEvent::new(KeyCode::Char('a'), KeyModifiers::empty()));
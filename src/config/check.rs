@@ -0,0 +1,288 @@
+use std::{collections::HashMap, fmt, path::Path};
+
+use color_eyre::eyre::{eyre, Result};
+use crossterm::event::KeyEvent;
+use serde_json::Value as JsonValue;
+
+use super::{
+  check_theme, key_sequence_to_string, parse_key_sequence, reading_defaults, validate_style, AutoPauseConfig,
+  BigFontConfig, NotificationsConfig, ReadingConfig, CONFIG, CONFIG_FILES, THEMES,
+};
+use crate::{action::Action, app::Mode, hyphenation};
+
+const SECTIONS: [&str; 6] = ["keybindings", "styles", "reading", "big_font", "auto_pause", "notifications"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+  Warning,
+  Error,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Severity::Warning => write!(f, "warning"),
+      Severity::Error => write!(f, "error"),
+    }
+  }
+}
+
+/// A problem found in a config file. `line` is 1-based when the offending key could be located.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+  pub severity: Severity,
+  pub line: Option<usize>,
+  pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.line {
+      Some(line) => write!(f, "{line}: {}: {}", self.severity, self.message),
+      None => write!(f, "{}: {}", self.severity, self.message),
+    }
+  }
+}
+
+/// Checks a config file, picking the format from its extension.
+pub fn check_file(path: &Path) -> Result<Vec<Diagnostic>> {
+  let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+  let format = CONFIG_FILES
+    .iter()
+    .find(|(file, _)| file.rsplit('.').next() == Some(extension))
+    .map(|(_, format)| *format)
+    .ok_or_else(|| eyre!("Unsupported config file format `{}`", path.display()))?;
+  Ok(check_source(&std::fs::read_to_string(path)?, format))
+}
+
+/// Validates a user config the way `Config::new` would read it, collecting every problem instead of stopping at
+/// the first one.
+pub fn check_source(source: &str, format: config::FileFormat) -> Vec<Diagnostic> {
+  let mut checker = Checker { source, diagnostics: Vec::new() };
+  let built = reading_defaults().and_then(|reading| {
+    config::Config::builder()
+      .set_default("reading", reading)?
+      .add_source(config::File::from_str(source, format))
+      .build()
+  });
+  let built = match built {
+    Ok(built) => built,
+    Err(e) => {
+      checker.error(&[], e.to_string());
+      return checker.diagnostics;
+    },
+  };
+  let value = match built.clone().try_deserialize::<JsonValue>() {
+    Ok(value) => value,
+    Err(e) => {
+      checker.error(&[], e.to_string());
+      return checker.diagnostics;
+    },
+  };
+
+  for key in value.as_object().into_iter().flat_map(|o| o.keys()) {
+    if !SECTIONS.contains(&key.as_str()) && !key.starts_with('_') {
+      checker.warning(&[key], format!("Unknown section `{key}`"));
+    }
+  }
+  if let Err(e) = built.get::<ReadingConfig>("reading") {
+    checker.error(&["reading"], e.to_string());
+  }
+  if let Some(Err(e)) = value.get("big_font").map(|_| built.get::<BigFontConfig>("big_font")) {
+    checker.error(&["big_font"], e.to_string());
+  }
+  if let Some(Err(e)) = value.get("auto_pause").map(|_| built.get::<AutoPauseConfig>("auto_pause")) {
+    checker.error(&["auto_pause"], e.to_string());
+  }
+  if let Some(Err(e)) = value.get("notifications").map(|_| built.get::<NotificationsConfig>("notifications")) {
+    checker.error(&["notifications"], e.to_string());
+  }
+  if let Some(theme) = value.pointer("/reading/theme").and_then(|t| t.as_str()) {
+    if let Err(e) = check_theme(theme) {
+      checker.error(&["reading", "theme"], e);
+    }
+  }
+  if let Some(language) = value.pointer("/reading/hyphenation/language").and_then(|l| l.as_str()) {
+    if hyphenation::patterns(language).is_none() {
+      checker.error(&["reading", "hyphenation", "language"], format!("No hyphenation patterns for `{language}`"));
+    }
+  }
+  if let Some(keybindings) = value.get("keybindings") {
+    checker.keybindings(keybindings);
+  }
+  if let Some(styles) = value.get("styles") {
+    checker.styles(styles);
+  }
+  checker.diagnostics.sort_by_key(|d| d.line);
+  checker.diagnostics
+}
+
+struct Checker<'a> {
+  source: &'a str,
+  diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+  fn error(&mut self, path: &[&str], message: String) {
+    let line = self.line(path);
+    self.diagnostics.push(Diagnostic { severity: Severity::Error, line, message });
+  }
+
+  fn warning(&mut self, path: &[&str], message: String) {
+    let line = self.line(path);
+    self.diagnostics.push(Diagnostic { severity: Severity::Warning, line, message });
+  }
+
+  /// Finds the line of a nested key by looking for each key in turn after the line of its parent.
+  fn line(&self, path: &[&str]) -> Option<usize> {
+    let lines: Vec<&str> = self.source.lines().collect();
+    let mut start = 0;
+    for key in path {
+      let found = lines[start..].iter().position(|line| declares_key(line, key))?;
+      start += found;
+    }
+    (!path.is_empty()).then_some(start + 1)
+  }
+
+  fn keybindings(&mut self, keybindings: &JsonValue) {
+    let defaults: JsonValue = json5::from_str(CONFIG).unwrap_or_default();
+    for (mode, bindings) in keybindings.as_object().into_iter().flatten() {
+      let Ok(parsed_mode) = serde_json::from_value::<Mode>(JsonValue::String(mode.clone())) else {
+        self.error(&["keybindings", mode], format!("Unknown mode `{mode}`"));
+        continue;
+      };
+      let mut parsed: HashMap<Vec<KeyEvent>, String> = HashMap::new();
+      for (keys, action) in bindings.as_object().into_iter().flatten() {
+        let path = ["keybindings", mode, keys];
+        if let Err(e) = serde_json::from_value::<Action>(action.clone()) {
+          self.error(&path, e.to_string());
+        }
+        match parse_key_sequence(keys) {
+          Ok(sequence) => {
+            if let Some(other) = parsed.get(&sequence) {
+              self.error(&path, format!("`{keys}` and `{other}` are bound to the same keys"));
+            } else {
+              parsed.insert(sequence, keys.clone());
+            }
+          },
+          Err(e) => self.error(&path, format!("Invalid key sequence `{keys}`: {e}")),
+        }
+      }
+      // User bindings are merged over the defaults, so a default single-key binding also shadows a user sequence.
+      // Looked up by the mode's current name, so that `Home` gets the defaults of `Reader`.
+      let default_bindings = defaults.pointer(&format!("/keybindings/{parsed_mode}")).and_then(|b| b.as_object());
+      for (keys, _) in default_bindings.into_iter().flatten() {
+        if let Ok(sequence) = parse_key_sequence(keys) {
+          parsed.entry(sequence).or_insert_with(|| keys.clone());
+        }
+      }
+      for (sequence, keys) in &parsed {
+        if sequence.len() < 2 || bindings.get(keys).is_none() {
+          continue;
+        }
+        if let Some(key) = sequence.iter().find(|key| parsed.contains_key(&vec![**key])) {
+          let shadow = key_sequence_to_string(&[*key]);
+          let path = ["keybindings", mode.as_str(), keys.as_str()];
+          self.warning(&path, format!("`{keys}` can never trigger because `{shadow}` is bound on its own"));
+        }
+      }
+    }
+  }
+
+  fn styles(&mut self, styles: &JsonValue) {
+    let names: Vec<&str> = THEMES[0].1.iter().map(|(name, _)| *name).collect();
+    for (mode, styles) in styles.as_object().into_iter().flatten() {
+      if serde_json::from_value::<Mode>(JsonValue::String(mode.clone())).is_err() {
+        self.error(&["styles", mode], format!("Unknown mode `{mode}`"));
+        continue;
+      }
+      for (name, style) in styles.as_object().into_iter().flatten() {
+        let path = ["styles", mode, name];
+        if !names.contains(&name.as_str()) {
+          self.warning(&path, format!("Unknown style `{name}`, expected one of: {}", names.join(", ")));
+        }
+        match style.as_str() {
+          Some(style) => {
+            if let Err(e) = validate_style(style) {
+              self.error(&path, e);
+            }
+          },
+          None => self.error(&path, format!("Style `{name}` must be a string")),
+        }
+      }
+    }
+  }
+}
+
+/// Whether `line` declares `key` in any of the supported formats, e.g. `"key":`, `key =` or `[section.key]`.
+fn declares_key(line: &str, key: &str) -> bool {
+  let line = line.trim_start();
+  ["\"", "'"].iter().any(|quote| line.contains(&format!("{quote}{key}{quote}")))
+    || line.strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with([':', '=']))
+    || line.strip_prefix('[').is_some_and(|header| header.trim_end().trim_end_matches(']').split('.').any(|k| k == key))
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn check(source: &str) -> Vec<(Severity, Option<usize>)> {
+    check_source(source, config::FileFormat::Json5).into_iter().map(|d| (d.severity, d.line)).collect()
+  }
+
+  #[test]
+  fn test_default_config_is_clean() {
+    assert_eq!(check_source(CONFIG, config::FileFormat::Json5), vec![]);
+  }
+
+  #[test]
+  fn test_reports_line_numbers() {
+    let source = r#"{
+  "keybindings": {
+    "Home": {
+      "<x>": "Launch",
+      "<ctrl-nope>": "Quit",
+      "<q>": "Quit",
+      "q": "Suspend",
+      "<j><k>": "Help"
+    }
+  },
+  "styles": {
+    "Home": {
+      "pivot": "purple",
+      "sparkle": "red"
+    }
+  },
+  "reading": { "theme": "neon", "wpm": "fast", "hyphenation": { "language": "xx" } },
+  "colour": true
+}"#;
+    assert_eq!(check(source), vec![
+      (Severity::Error, Some(4)),
+      (Severity::Error, Some(5)),
+      (Severity::Error, Some(7)),
+      (Severity::Warning, Some(8)),
+      (Severity::Error, Some(13)),
+      (Severity::Warning, Some(14)),
+      (Severity::Error, Some(17)),
+      (Severity::Error, Some(17)),
+      (Severity::Error, Some(17)),
+      (Severity::Warning, Some(18)),
+    ]);
+  }
+
+  #[test]
+  fn test_toml_line_numbers() {
+    let source = "[reading]\nwpm = 300\ntheme = \"neon\"\n\n[keybindings.Home]\n\"<x>\" = \"Launch\"\n";
+    let diagnostics = check_source(source, config::FileFormat::Toml);
+    assert_eq!(diagnostics.iter().map(|d| d.line).collect::<Vec<_>>(), vec![Some(3), Some(6)]);
+  }
+
+  #[test]
+  fn test_parse_error() {
+    let diagnostics = check_source("{ \"reading\": ", config::FileFormat::Json5);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+  }
+}
@@ -0,0 +1,202 @@
+use std::{collections::HashMap, path::Path};
+
+use color_eyre::eyre::{eyre, Result};
+
+const BUILTIN: &str = include_str!("../assets/fonts/block.flf");
+
+// Code points that follow ASCII in every FIGlet font, before the code-tagged characters.
+const DEUTSCH: [u32; 7] = [196, 214, 220, 228, 246, 252, 223];
+
+/// A FIGlet (`.flf`) font, rendered at full width without smushing.
+#[derive(Clone, Debug, Default)]
+pub struct Font {
+  height: usize,
+  glyphs: HashMap<char, Vec<String>>,
+}
+
+impl Font {
+  pub fn builtin() -> Self {
+    Self::parse(BUILTIN).expect("built-in font is valid")
+  }
+
+  /// Loads `name` from `<config_dir>/fonts/<name>.flf`, or `name` itself if it is a path.
+  /// `block` is the built-in font.
+  pub fn load(name: &str, config_dir: &Path) -> Result<Self> {
+    if name == "block" {
+      return Ok(Self::builtin());
+    }
+    let path = if name.ends_with(".flf") {
+      config_dir.join(name)
+    } else {
+      config_dir.join("fonts").join(format!("{name}.flf"))
+    };
+    let flf = std::fs::read_to_string(&path).map_err(|e| eyre!("Unable to read font {}: {e}", path.display()))?;
+    Self::parse(&flf).map_err(|e| eyre!("Unable to parse font {}: {e}", path.display()))
+  }
+
+  pub fn parse(flf: &str) -> Result<Self> {
+    let mut lines = flf.lines();
+    let header = lines.next().ok_or_else(|| eyre!("empty font file"))?;
+    let signature = header.split_whitespace().next().unwrap_or_default();
+    let hardblank =
+      signature.strip_prefix("flf2a").and_then(|s| s.chars().next()).ok_or_else(|| eyre!("not a FIGlet font"))?;
+    let fields: Vec<usize> = header.split_whitespace().skip(1).take(5).map(|f| f.parse().unwrap_or(0)).collect();
+    let (height, comment_lines) = match fields.as_slice() {
+      [height, _, _, _, comment_lines, ..] if *height > 0 => (*height, *comment_lines),
+      _ => return Err(eyre!("invalid header `{header}`")),
+    };
+    let mut lines = lines.skip(comment_lines).peekable();
+
+    let read_glyph = |lines: &mut dyn Iterator<Item = &str>| -> Option<Vec<String>> {
+      let mut rows: Vec<String> = Vec::with_capacity(height);
+      for _ in 0..height {
+        let line = lines.next()?.trim_end();
+        let endmark = line.chars().last()?;
+        rows.push(line.trim_end_matches(endmark).replace(hardblank, " "));
+      }
+      let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+      for row in rows.iter_mut() {
+        let pad = width - row.chars().count();
+        row.extend(std::iter::repeat_n(' ', pad));
+      }
+      Some(rows)
+    };
+
+    let mut glyphs = HashMap::new();
+    for code in (32..127).chain(DEUTSCH) {
+      match read_glyph(&mut lines) {
+        Some(rows) => glyphs.insert(char::from_u32(code).unwrap(), rows),
+        None if code > 126 => break,
+        None => return Err(eyre!("missing glyph for `{}`", char::from_u32(code).unwrap())),
+      };
+    }
+    while let Some(tag) = lines.next() {
+      if tag.trim().is_empty() {
+        continue;
+      }
+      let code = parse_code(tag.split_whitespace().next().unwrap_or_default())
+        .ok_or_else(|| eyre!("invalid character code `{tag}`"))?;
+      let rows = read_glyph(&mut lines).ok_or_else(|| eyre!("incomplete glyph for `{tag}`"))?;
+      if let Some(c) = u32::try_from(code).ok().and_then(char::from_u32) {
+        glyphs.insert(c, rows);
+      }
+    }
+    Ok(Self { height, glyphs })
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn glyph(&self, c: char) -> Option<&[String]> {
+    self.glyphs.get(&c).map(|rows| rows.as_slice())
+  }
+
+  /// Width of `text` in cells, or `None` if the font is missing one of its characters.
+  pub fn width(&self, text: &str) -> Option<usize> {
+    text.chars().map(|c| self.glyph(c).map(glyph_width)).sum()
+  }
+
+  pub fn render(&self, text: &str) -> Option<Vec<String>> {
+    let glyphs = text.chars().map(|c| self.glyph(c)).collect::<Option<Vec<_>>>()?;
+    Some((0..self.height).map(|row| glyphs.iter().map(|g| g[row].as_str()).collect()).collect())
+  }
+}
+
+pub fn glyph_width(rows: &[String]) -> usize {
+  rows.first().map_or(0, |r| r.chars().count())
+}
+
+/// Enlarges rendered rows by repeating every cell `scale` times in both directions.
+pub fn scale(rows: &[String], scale: usize) -> Vec<String> {
+  rows
+    .iter()
+    .flat_map(|row| {
+      let wide: String = row.chars().flat_map(|c| std::iter::repeat_n(c, scale)).collect();
+      std::iter::repeat_n(wide, scale)
+    })
+    .collect()
+}
+
+fn parse_code(raw: &str) -> Option<i64> {
+  let (negative, raw) = match raw.strip_prefix('-') {
+    Some(rest) => (true, rest),
+    None => (false, raw),
+  };
+  let code = if let Some(hex) = raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+    i64::from_str_radix(hex, 16).ok()?
+  } else if raw.len() > 1 && raw.starts_with('0') {
+    i64::from_str_radix(&raw[1..], 8).ok()?
+  } else {
+    raw.parse().ok()?
+  };
+  Some(if negative { -code } else { code })
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn tiny_font() -> String {
+    let mut flf = String::from("flf2a$ 2 2 4 -1 1\nA tiny test font\n");
+    for code in (32..127).chain(DEUTSCH) {
+      let c = char::from_u32(code).unwrap();
+      flf.push_str(&format!("{c}$@\n{c}{c}@@\n"));
+    }
+    flf.push_str("0x263A  WHITE SMILING FACE\n:)#\n:(##\n");
+    flf.push_str("-2  ignored\nxx@\nxx@@\n");
+    flf
+  }
+
+  #[test]
+  fn test_builtin_coverage() {
+    let font = Font::builtin();
+    assert_eq!(font.height(), 7);
+    for code in (32..127).chain(160..256) {
+      let c = char::from_u32(code).unwrap();
+      assert!(font.glyph(c).is_some(), "missing {c:?}");
+    }
+  }
+
+  #[test]
+  fn test_parse() {
+    let font = Font::parse(&tiny_font()).unwrap();
+    assert_eq!(font.height(), 2);
+    assert_eq!(font.glyph('a').unwrap(), ["a ".to_string(), "aa".to_string()]);
+    assert_eq!(font.glyph('☺').unwrap(), [":)".to_string(), ":(".to_string()]);
+    assert_eq!(font.glyph('Ä').unwrap(), ["Ä ".to_string(), "ÄÄ".to_string()]);
+    assert_eq!(font.glyph('é'), None);
+  }
+
+  #[test]
+  fn test_render() {
+    let font = Font::parse(&tiny_font()).unwrap();
+    assert_eq!(font.render("ab").unwrap(), vec!["a b ".to_string(), "aabb".to_string()]);
+    assert_eq!(font.width("ab"), Some(4));
+    assert_eq!(font.render("aé"), None);
+    assert_eq!(font.width("aé"), None);
+  }
+
+  #[test]
+  fn test_scale() {
+    assert_eq!(scale(&["a ".to_string()], 2), vec!["aa  ".to_string(), "aa  ".to_string()]);
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    assert!(Font::parse("").is_err());
+    assert!(Font::parse("not a font").is_err());
+    assert!(Font::parse("flf2a$ 2 2 4 -1 0\na@\n").is_err());
+  }
+
+  #[test]
+  fn test_parse_code() {
+    assert_eq!(parse_code("196"), Some(196));
+    assert_eq!(parse_code("0xC4"), Some(196));
+    assert_eq!(parse_code("0304"), Some(196));
+    assert_eq!(parse_code("-2"), Some(-2));
+    assert_eq!(parse_code("x"), None);
+  }
+}
@@ -0,0 +1,104 @@
+use hypher::Lang;
+
+/// Splits a word into syllables. The bundled patterns implement this for every language compiled into `hypher`
+/// (English unless built with the `all-languages` feature); other languages can provide their own.
+pub trait Hyphenator {
+  fn syllables<'a>(&self, word: &'a str) -> Vec<&'a str>;
+}
+
+impl Hyphenator for Lang {
+  fn syllables<'a>(&self, word: &'a str) -> Vec<&'a str> {
+    hypher::hyphenate(word, *self).collect()
+  }
+}
+
+/// Bundled patterns for an ISO 639-1 language code such as `en`.
+pub fn patterns(language: &str) -> Option<Lang> {
+  Lang::from_iso(language.as_bytes().try_into().ok()?)
+}
+
+/// Splits a word longer than `max_length` letters into pieces of roughly equal length that are shown one after
+/// another. Compounds are split at their hyphens and dashes first, then long parts at syllable boundaries with a
+/// trailing hyphen. Returns the word unchanged when it is short or has no usable break.
+pub fn split_word(word: &str, max_length: usize, hyphenator: &dyn Hyphenator) -> Vec<String> {
+  if max_length == 0 || word.chars().count() <= max_length {
+    return vec![word.to_string()];
+  }
+  let mut pieces = Vec::new();
+  for part in word.split_inclusive(['-', '—']) {
+    let (lead, rest) = part.split_at(part.find(char::is_alphanumeric).unwrap_or(part.len()));
+    let end = rest.char_indices().rev().find(|(_, c)| c.is_alphanumeric()).map_or(0, |(i, c)| i + c.len_utf8());
+    let (core, trail) = rest.split_at(end);
+    let length = core.chars().count();
+    if length <= max_length {
+      pieces.push(part.to_string());
+      continue;
+    }
+    let syllables = hyphenator.syllables(core);
+    let mut split = balance(&syllables, length.div_ceil(max_length), length);
+    let last = split.len() - 1;
+    for piece in &mut split[..last] {
+      piece.push('-');
+    }
+    split[0].insert_str(0, lead);
+    split[last].push_str(trail);
+    pieces.extend(split);
+  }
+  pieces
+}
+
+/// Joins `syllables` into `count` pieces, cutting at the syllable boundary closest to each even share of `length`.
+fn balance(syllables: &[&str], count: usize, length: usize) -> Vec<String> {
+  let mut pieces = vec![String::new()];
+  let mut done: usize = 0;
+  for syllable in syllables {
+    let target = length * pieces.len() / count;
+    let len = syllable.chars().count();
+    let piece = pieces.last().map_or(0, |p| p.chars().count());
+    if piece > 0 && pieces.len() < count && done.abs_diff(target) < (done + len).abs_diff(target) {
+      pieces.push(String::new());
+    }
+    if let Some(piece) = pieces.last_mut() {
+      piece.push_str(syllable);
+    }
+    done += len;
+  }
+  pieces
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn split(word: &str, max_length: usize) -> Vec<String> {
+    split_word(word, max_length, &Lang::English)
+  }
+
+  #[test]
+  fn test_short_words_are_kept() {
+    assert_eq!(split("extensive", 12), vec!["extensive"]);
+    assert_eq!(split("extensive", 0), vec!["extensive"]);
+  }
+
+  #[test]
+  fn test_syllable_pieces() {
+    assert_eq!(split("incomprehensibilities", 12), vec!["incomprehen-", "sibilities"]);
+    assert_eq!(split("“Incomprehensibilities,”", 8), vec!["“Incompre-", "hensi-", "bilities,”"]);
+  }
+
+  #[test]
+  fn test_compounds() {
+    assert_eq!(split("waistcoat-pocket", 12), vec!["waistcoat-", "pocket"]);
+    assert_eq!(split("Hatter—extraordinarily", 12), vec!["Hatter—", "extraor-", "dinarily"]);
+    assert_eq!(split("Hatter—sometimes", 12), vec!["Hatter—", "sometimes"]);
+  }
+
+  #[test]
+  fn test_patterns() {
+    assert!(patterns("en").is_some());
+    assert!(patterns("english").is_none());
+    assert!(patterns("").is_none());
+  }
+}
@@ -8,26 +8,46 @@ pub mod app;
 pub mod cli;
 pub mod components;
 pub mod config;
+pub mod font;
+pub mod hyphenation;
+pub mod pipe;
+pub mod playback;
+pub mod remote;
+pub mod session;
+pub mod state;
+pub mod text;
 pub mod tui;
 pub mod utils;
 
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command};
 use color_eyre::eyre::Result;
 
-use console_subscriber::ConsoleLayer;
-
 use crate::{
   app::App,
+  pipe::Pipe,
   utils::{initialize_logging, initialize_panic_handler, version},
 };
 
 async fn tokio_main() -> Result<()> {
+  initialize_logging()?;
 
   initialize_panic_handler()?;
 
   let args = Cli::parse();
-  let mut app = App::new(args.tick_rate, args.frame_rate)?;
+  if let Some(Command::Config(command)) = &args.command {
+    if !command.run(&args)? {
+      std::process::exit(1);
+    }
+    return Ok(());
+  }
+  if args.pipe {
+    if !Pipe::new(args)?.run().await? {
+      std::process::exit(1);
+    }
+    return Ok(());
+  }
+  let mut app = App::new(args)?;
   app.run().await?;
 
   Ok(())
@@ -35,9 +55,6 @@ async fn tokio_main() -> Result<()> {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-
-  ConsoleLayer::builder().with_default_env().init();
-
   if let Err(e) = tokio_main().await {
     eprintln!("{} error: Something went wrong", env!("CARGO_PKG_NAME"));
     Err(e)
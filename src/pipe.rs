@@ -0,0 +1,247 @@
+use std::{
+  io::{self, Write},
+  thread,
+};
+
+use color_eyre::eyre::Result;
+use crossterm::{
+  queue,
+  style::{Attribute, ContentStyle, Print, StyledContent},
+  terminal::{Clear, ClearType},
+};
+use ratatui::style::{Modifier, Style};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+  action::Action,
+  cli::Cli,
+  components::{
+    home::{visual_pivot, Home},
+    notifications::Severity,
+    Component,
+  },
+  config::{check_theme, Config},
+  text::{self, is_break},
+};
+
+/// Column of the pivot letter when words overwrite each other, enough for the letters `orp_index` puts before it.
+const PIVOT_COLUMN: usize = 8;
+
+/// Reads without the terminal UI: `Home` loads and times the text as usual while each word is written to stdout, and
+/// lines on stdin are taken as commands, see `command`.
+pub struct Pipe {
+  pub args: Cli,
+  pub config: Config,
+  pub home: Home,
+  /// Position of the last word written, as the current token and piece.
+  pub shown: Option<(usize, usize)>,
+}
+
+impl Pipe {
+  pub fn new(args: Cli) -> Result<Self> {
+    let home = Home::new().file(args.file.clone()).overrides(args.reading_overrides());
+    let mut config = Config::new()?;
+    args.apply_overrides(&mut config);
+    Ok(Self { args, config, home, shown: None })
+  }
+
+  /// Reads the text to the end or until told to quit. Returns whether the text could be read.
+  pub async fn run(&mut self) -> Result<bool> {
+    let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+    self.home.register_action_handler(action_tx.clone())?;
+    self.home.register_config_handler(self.config.clone())?;
+    self.home.theme_changed(&self.config.reading.theme);
+    self.home.init()?;
+    Self::read_commands(action_tx);
+
+    let (mut loaded, mut started, mut failed) = (false, false, false);
+    let result = 'run: loop {
+      let action = tokio::select! {
+        action = action_rx.recv() => action,
+        _ = tokio::signal::ctrl_c() => Some(Action::Quit),
+      };
+      let Some(mut action) = action else {
+        break Ok(true);
+      };
+      // Actions that follow from one another, such as resuming the last position after loading, are all handled
+      // before a word is written.
+      loop {
+        if action == Action::Quit {
+          self.home.update(action)?;
+          break 'run Ok(true);
+        }
+        if let Some((_, message)) = Severity::of(&action) {
+          eprintln!("{message}");
+        }
+        let loading = action == Action::TextLoad();
+        loaded |= loading;
+        // Without the app to own the theme, the book's one is taken here.
+        if let Action::SetTheme(ref theme) = action {
+          if check_theme(theme).is_ok() {
+            self.home.theme_changed(theme);
+          }
+        }
+        self.home.update(action)?;
+        // Why is told by the `Error` that follows.
+        failed |= loading && self.home.text_key.is_none();
+        match action_rx.try_recv() {
+          Ok(next) => action = next,
+          Err(_) => break,
+        }
+      }
+      if failed {
+        break Ok(false);
+      }
+      if !started && self.home.text_length > 0 {
+        started = true;
+        self.home.play_text();
+      }
+      match self.show() {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break Ok(true),
+        result => result?,
+      }
+      // An empty text never starts.
+      if (started || loaded && self.home.text_length == 0) && self.finished() {
+        break Ok(true);
+      }
+    };
+    if self.args.overwrite && self.shown.is_some() {
+      println!();
+    }
+    result
+  }
+
+  /// Whether playback stopped on the last words, rather than being paused.
+  fn finished(&self) -> bool {
+    let home = &self.home;
+    !home.text_play_on
+      && home.resume_handle.is_none()
+      && home.text_batches.is_none()
+      && home.chunk_end(home.text_current_index) >= home.text_length
+  }
+
+  /// Writes the current word if it changed. Line and stanza breaks only pause, so nothing is written for them.
+  fn show(&mut self) -> io::Result<()> {
+    let position = (self.home.text_current_index, self.home.text_piece);
+    let word = self.home.current_word();
+    if self.shown == Some(position) || word.is_empty() || is_break(word) {
+      return Ok(());
+    }
+    self.shown = Some(position);
+    let pivot = (self.args.pivot && self.home.reading.orp).then(|| self.home.style("pivot"));
+    let line = format_word(word, pivot, self.args.overwrite);
+    let mut stdout = io::stdout().lock();
+    match self.args.overwrite {
+      true => queue!(stdout, Print("\r"), Clear(ClearType::CurrentLine), Print(line))?,
+      false => queue!(stdout, Print(line), Print("\n"))?,
+    }
+    stdout.flush()
+  }
+
+  /// Sends the actions for the commands typed on stdin, until it is closed. This is a thread of its own rather than a
+  /// task, as the runtime would wait for a blocked read of stdin before exiting.
+  fn read_commands(action_tx: UnboundedSender<Action>) {
+    thread::spawn(move || {
+      for line in io::stdin().lines().map_while(Result::ok) {
+        match command(&line) {
+          Ok(action) => {
+            if action_tx.send(action).is_err() {
+              break;
+            }
+          },
+          Err(e) => eprintln!("{e}"),
+        }
+      }
+    });
+  }
+}
+
+/// Parses a line from stdin: an empty line or `p` toggles playback, `n`/`b` skip words forward/back, `>`/`<`
+/// paragraphs, `+`/`-` change the speed and `q` quits, each with an optional count. Anything else is read as an
+/// action, e.g. `NextParagraph(2)`.
+pub fn command(line: &str) -> Result<Action, String> {
+  let line = line.trim();
+  let (name, count) = match line.split_once(char::is_whitespace) {
+    Some((name, count)) => {
+      let count = count.trim().parse::<usize>().map_err(|_| format!("Invalid count in `{line}`"))?;
+      (name, count)
+    },
+    None => (line, 1),
+  };
+  let wpm = || {
+    let wpm = u32::try_from(count).ok().and_then(|count| count.checked_mul(25));
+    wpm.ok_or_else(|| format!("Count too large in `{line}`"))
+  };
+  let action = match name {
+    "" | "p" | "pause" => Action::ScheduleSreadText,
+    "n" | "next" | "skip" => Action::IncrementText(count),
+    "b" | "back" => Action::DecrementText(count),
+    ">" => Action::NextParagraph(count),
+    "<" => Action::PreviousParagraph(count),
+    "+" => Action::IncreaseWpm(wpm()?),
+    "-" => Action::DecreaseWpm(wpm()?),
+    "q" | "quit" => Action::Quit,
+    _ => line.parse()?,
+  };
+  Ok(action)
+}
+
+/// The word as written to stdout, with its pivot letter in `pivot` style when given. Words that overwrite each other
+/// are padded so that their pivots line up.
+pub fn format_word(word: &str, pivot: Option<Style>, align: bool) -> String {
+  let Some(style) = pivot else {
+    return word.to_string();
+  };
+  let graphemes = text::visual_order(word);
+  let index = visual_pivot(&graphemes);
+  let join = |graphemes: &[(usize, &str)]| graphemes.iter().map(|(_, g)| *g).collect::<String>();
+  let before = join(&graphemes[..index]);
+  let padding = match align {
+    true => PIVOT_COLUMN.saturating_sub(before.width()),
+    false => 0,
+  };
+  format!("{}{before}{}{}", " ".repeat(padding), ansi(style, graphemes[index].1), join(&graphemes[index + 1..]))
+}
+
+fn ansi(style: Style, s: &str) -> String {
+  let mut content = ContentStyle::new();
+  content.foreground_color = style.fg.map(Into::into);
+  content.background_color = style.bg.map(Into::into);
+  if style.add_modifier.contains(Modifier::BOLD) {
+    content.attributes.set(Attribute::Bold);
+  }
+  StyledContent::new(content, s).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use ratatui::style::Color;
+
+  use super::*;
+
+  #[test]
+  fn test_command() {
+    assert_eq!(command(""), Ok(Action::ScheduleSreadText));
+    assert_eq!(command(" p "), Ok(Action::ScheduleSreadText));
+    assert_eq!(command("n"), Ok(Action::IncrementText(1)));
+    assert_eq!(command("b 10"), Ok(Action::DecrementText(10)));
+    assert_eq!(command("> 2"), Ok(Action::NextParagraph(2)));
+    assert_eq!(command("+"), Ok(Action::IncreaseWpm(25)));
+    assert_eq!(command("- 4"), Ok(Action::DecreaseWpm(100)));
+    assert_eq!(command("+ 200000000"), Err("Count too large in `+ 200000000`".to_string()));
+    assert_eq!(command("q"), Ok(Action::Quit));
+    assert_eq!(command("ToggleContext"), Ok(Action::ToggleContext));
+    assert_eq!(command("n x"), Err("Invalid count in `n x`".to_string()));
+    assert!(command("jump").unwrap_err().starts_with("Unknown action `jump`"));
+  }
+
+  #[test]
+  fn test_format_word() {
+    let red = Style::default().fg(Color::Red);
+    assert_eq!(format_word("Alice", None, true), "Alice");
+    assert_eq!(format_word("Alice", Some(red), false), "A\u{1b}[38;5;1ml\u{1b}[39mice");
+    assert_eq!(format_word("Alice", Some(red), true), "       A\u{1b}[38;5;1ml\u{1b}[39mice");
+  }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Due times of the words during playback. Each word is due a delay after the previous one was due rather than after
+/// it was shown, so time spent passing actions around and drawing does not add up over a book.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Schedule {
+  started: Instant,
+  /// When the word on screen is replaced by the next one.
+  due: Instant,
+  /// When the last word was replaced, and when it was due to be.
+  shown: Instant,
+  shown_due: Instant,
+  words: usize,
+}
+
+impl Schedule {
+  pub fn start() -> Self {
+    let now = Instant::now();
+    Schedule { started: now, due: now, shown: now, shown_due: now, words: 0 }
+  }
+
+  /// Schedules the end of the word on screen `delay` after the previous word was due, or right away when that has
+  /// passed already. A stall, such as the process being stopped, is not made up for with a burst of words.
+  pub fn next(&mut self, delay: Duration) -> Instant {
+    self.due = (self.due + delay).max(Instant::now());
+    self.due
+  }
+
+  /// Records that the words on screen, `words` of them, have been replaced. Returns how long they were on screen.
+  pub fn shown(&mut self, words: usize) -> Duration {
+    let now = Instant::now();
+    let on_screen = now - self.shown;
+    self.words += words;
+    self.shown = now;
+    self.shown_due = self.due;
+    on_screen
+  }
+
+  /// Words per minute actually read since playback started.
+  pub fn measured_wpm(&self) -> f64 {
+    wpm(self.words, self.shown - self.started)
+  }
+
+  /// Words per minute the schedule asked for, including pauses.
+  pub fn target_wpm(&self) -> f64 {
+    wpm(self.words, self.shown_due - self.started)
+  }
+}
+
+fn wpm(words: usize, elapsed: Duration) -> f64 {
+  match elapsed.is_zero() {
+    true => 0.0,
+    false => words as f64 * 60.0 / elapsed.as_secs_f64(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[tokio::test(start_paused = true)]
+  async fn test_late_words_do_not_delay_the_next() {
+    let mut schedule = Schedule::start();
+    let start = Instant::now();
+    for _ in 0..100 {
+      let due = schedule.next(Duration::from_millis(200));
+      tokio::time::sleep_until(due + Duration::from_millis(30)).await;
+      schedule.shown(1);
+    }
+    assert_eq!(Instant::now() - start, Duration::from_millis(20_030));
+    assert_eq!(schedule.target_wpm(), 300.0);
+    assert!((schedule.measured_wpm() - 300.0).abs() < 0.5);
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_stalls_are_not_made_up() {
+    let mut schedule = Schedule::start();
+    let delay = Duration::from_millis(200);
+    let due = schedule.next(delay);
+    tokio::time::sleep_until(due).await;
+    assert_eq!(schedule.shown(1), delay);
+    tokio::time::advance(Duration::from_secs(5)).await;
+    let stalled = Instant::now();
+    // The late word goes right away, and the ones after it keep the pace from there.
+    assert_eq!(schedule.next(delay), stalled);
+    schedule.shown(1);
+    assert_eq!(schedule.next(delay), stalled + delay);
+    assert_eq!(schedule.next(delay), stalled + delay * 2);
+  }
+}
@@ -0,0 +1,237 @@
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+  sync::{
+    broadcast::{self, error::RecvError},
+    mpsc::UnboundedSender,
+  },
+  task::JoinHandle,
+};
+
+use crate::action::Action;
+
+/// What a running reader tells the programs connected to it, one JSON object per line, e.g.
+/// `{"event":"word","index":12,"word":"Alice","wpm":300}`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+  /// A word was shown, `index` being its position in the text as used by `seek`.
+  Word { index: usize, word: String, wpm: u32 },
+  /// Playback moved on from the words on screen, `words` of them, after `ms` milliseconds. Unlike `Word`, which is
+  /// also reported when moving around the text, this counts them as read.
+  Read { words: usize, ms: u64 },
+  /// Reading entered the `index`th chapter, counting from 1, whose heading starts at `position`.
+  Chapter { index: usize, position: usize, title: String },
+  /// Playback reached the end of the text.
+  Finished,
+  /// A command could not be understood.
+  Error { message: String },
+}
+
+/// What programs connected to a running reader can ask of it, one JSON object per line, e.g.
+/// `{"command":"seek","position":120}`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Command {
+  Play,
+  Pause,
+  Seek { position: usize },
+  SetWpm { wpm: u32 },
+  Open { path: String },
+}
+
+impl From<Command> for Action {
+  fn from(command: Command) -> Self {
+    match command {
+      Command::Play => Action::Play,
+      Command::Pause => Action::Pause,
+      Command::Seek { position } => Action::Seek(position),
+      Command::SetWpm { wpm } => Action::SetWpm(wpm),
+      Command::Open { path } => Action::TextOpen(path),
+    }
+  }
+}
+
+/// Listens on a Unix domain socket for commands, which are sent on as actions, and streams `Event`s back to every
+/// connection. The socket is removed again when this is dropped.
+#[cfg(unix)]
+pub struct Remote {
+  pub path: PathBuf,
+  pub task: JoinHandle<()>,
+}
+
+#[cfg(unix)]
+impl Remote {
+  /// Listens on `path`, replacing a socket left behind by an instance that is no longer running. Anything else found
+  /// at `path` is left alone and reported as an error.
+  pub fn start(
+    path: PathBuf,
+    action_tx: UnboundedSender<Action>,
+    events: broadcast::Sender<Event>,
+  ) -> io::Result<Self> {
+    let existing = match fs::symlink_metadata(&path) {
+      Ok(metadata) => Some(metadata),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+      Err(e) => return Err(e),
+    };
+    if let Some(metadata) = existing {
+      if !metadata.file_type().is_socket() {
+        let message = format!("{} exists and is not a socket", path.display());
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, message));
+      }
+      if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+        let message = format!("{} is in use by another instance", path.display());
+        return Err(io::Error::new(io::ErrorKind::AddrInUse, message));
+      }
+      fs::remove_file(&path)?;
+    }
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    let task = tokio::spawn(async move {
+      loop {
+        match listener.accept().await {
+          Ok((stream, _)) => {
+            tokio::spawn(serve(stream, action_tx.clone(), events.subscribe()));
+          },
+          Err(e) => {
+            log::error!("Failed to accept a remote control connection: {e}");
+            break;
+          },
+        }
+      }
+    });
+    log::info!("Listening for remote control on {}", path.display());
+    Ok(Self { path, task })
+  }
+}
+
+#[cfg(unix)]
+impl Drop for Remote {
+  fn drop(&mut self) {
+    self.task.abort();
+    if let Err(e) = fs::remove_file(&self.path) {
+      log::error!("Failed to remove {}: {e}", self.path.display());
+    }
+  }
+}
+
+/// Handles one connection until either side closes it.
+#[cfg(unix)]
+async fn serve(
+  stream: tokio::net::UnixStream,
+  action_tx: UnboundedSender<Action>,
+  mut events: broadcast::Receiver<Event>,
+) {
+  let (reader, mut writer) = stream.into_split();
+  let mut lines = BufReader::new(reader).lines();
+  loop {
+    let event = tokio::select! {
+      line = lines.next_line() => match line {
+        Ok(Some(line)) if line.trim().is_empty() => continue,
+        Ok(Some(line)) => match serde_json::from_str::<Command>(&line) {
+          Ok(command) => {
+            if action_tx.send(command.into()).is_err() {
+              break;
+            }
+            continue;
+          },
+          Err(e) => Event::Error { message: format!("Invalid command `{line}`: {e}") },
+        },
+        _ => break,
+      },
+      event = events.recv() => match event {
+        Ok(event) => event,
+        // A client too slow to keep up misses words rather than holding up the reader.
+        Err(RecvError::Lagged(_)) => continue,
+        Err(RecvError::Closed) => break,
+      },
+    };
+    let mut line = serde_json::to_string(&event).expect("events serialize");
+    line.push('\n');
+    if writer.write_all(line.as_bytes()).await.is_err() {
+      break;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use tokio::{io::AsyncBufReadExt, net::UnixStream, sync::mpsc};
+
+  use super::*;
+
+  #[test]
+  fn test_command() {
+    let command = |json: &str| serde_json::from_str::<Command>(json).map(Action::from).map_err(|e| e.to_string());
+    assert_eq!(command(r#"{"command":"play"}"#), Ok(Action::Play));
+    assert_eq!(command(r#"{"command":"pause"}"#), Ok(Action::Pause));
+    assert_eq!(command(r#"{"command":"seek","position":120}"#), Ok(Action::Seek(120)));
+    assert_eq!(command(r#"{"command":"set_wpm","wpm":400}"#), Ok(Action::SetWpm(400)));
+    assert_eq!(command(r#"{"command":"open","path":"alice.txt"}"#), Ok(Action::TextOpen("alice.txt".into())));
+    assert!(command(r#"{"command":"seek"}"#).unwrap_err().starts_with("missing field `position`"));
+    assert!(command(r#"{"command":"rewind"}"#).unwrap_err().starts_with("unknown variant `rewind`"));
+  }
+
+  #[test]
+  fn test_event() {
+    let json = |event: Event| serde_json::to_string(&event).unwrap();
+    assert_eq!(
+      json(Event::Word { index: 3, word: "Alice".into(), wpm: 300 }),
+      r#"{"event":"word","index":3,"word":"Alice","wpm":300}"#
+    );
+    assert_eq!(
+      json(Event::Chapter { index: 2, position: 40, title: "CHAPTER II.".into() }),
+      r#"{"event":"chapter","index":2,"position":40,"title":"CHAPTER II."}"#
+    );
+    assert_eq!(json(Event::Read { words: 3, ms: 600 }), r#"{"event":"read","words":3,"ms":600}"#);
+    assert_eq!(json(Event::Finished), r#"{"event":"finished"}"#);
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_remote() {
+    let path = std::env::temp_dir().join(format!("sreader-test-{}.sock", std::process::id()));
+    let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+    let (events, _) = broadcast::channel(16);
+    let remote = Remote::start(path.clone(), action_tx.clone(), events.clone()).unwrap();
+    let err = Remote::start(path.clone(), action_tx, events.clone()).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+    let (reader, mut writer) = UnixStream::connect(&path).await.unwrap().into_split();
+    let mut lines = BufReader::new(reader).lines();
+    writer.write_all(b"{\"command\":\"seek\",\"position\":7}\n\nnonsense\n").await.unwrap();
+    assert_eq!(action_rx.recv().await, Some(Action::Seek(7)));
+    let error = lines.next_line().await.unwrap().unwrap();
+    assert!(error.starts_with(r#"{"event":"error","message":"Invalid command `nonsense`"#));
+
+    events.send(Event::Finished).unwrap();
+    assert_eq!(lines.next_line().await.unwrap().unwrap(), r#"{"event":"finished"}"#);
+
+    drop(remote);
+    assert!(!path.exists());
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_start_replaces_only_sockets() {
+    let path = std::env::temp_dir().join(format!("sreader-test-{}-stale.sock", std::process::id()));
+    let (action_tx, _action_rx) = mpsc::unbounded_channel();
+    let (events, _) = broadcast::channel(16);
+    // A socket nothing listens on any more is replaced.
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    drop(Remote::start(path.clone(), action_tx.clone(), events.clone()).unwrap());
+
+    fs::write(&path, "notes").unwrap();
+    let err = Remote::start(path.clone(), action_tx, events).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "notes");
+    fs::remove_file(&path).unwrap();
+  }
+}
@@ -0,0 +1,213 @@
+use std::{
+  fs::File,
+  io::{self, BufRead, BufReader, BufWriter, Write},
+  path::{Path, PathBuf},
+  time::Duration,
+};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize, Serializer};
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle, time::Instant};
+
+use crate::action::Action;
+
+/// Version of the log format, written in the header and checked on replay.
+pub const VERSION: u32 = 1;
+
+/// The first line of a log, e.g. `{"version":1,"file":"alice.txt"}`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+  pub version: u32,
+  /// The book given on the command line, read again on replay unless another one is given.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub file: Option<PathBuf>,
+}
+
+/// A line of a log after the header: an action and when it was handled, in milliseconds since recording started, e.g.
+/// `{"ms":5120,"action":"IncrementText(10)"}`. Actions are written the way keybindings name them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+  pub ms: u64,
+  #[serde(serialize_with = "display")]
+  pub action: Action,
+}
+
+fn display<S: Serializer>(action: &Action, serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.collect_str(action)
+}
+
+/// Whether `action` is part of a session. Actions that follow from the terminal, such as frames and resizes, or from
+/// the passing of time, such as ticks and batches of tokens arriving, are neither recorded nor replayed: a replay
+/// gets them from the terminal it runs in. Quitting is also left to whoever watches the replay, and notifications are
+/// those of the replay itself, so that its failures are shown.
+pub fn is_recorded(action: &Action) -> bool {
+  !matches!(
+    action,
+    Action::Tick
+      | Action::Render
+      | Action::Redraw
+      | Action::Resize(..)
+      | Action::Suspend
+      | Action::Resume
+      | Action::Quit
+      | Action::ReloadConfig
+      | Action::TextLoaded
+      | Action::Error(..)
+      | Action::Warning(..)
+      | Action::Info(..)
+  )
+}
+
+/// Writes the actions of a session to a JSON-lines log as they are handled.
+pub struct Recorder {
+  writer: BufWriter<File>,
+  started: Instant,
+}
+
+impl Recorder {
+  pub fn create(path: &Path, file: Option<PathBuf>) -> io::Result<Self> {
+    let mut recorder = Self { writer: BufWriter::new(File::create(path)?), started: Instant::now() };
+    recorder.write(&Header { version: VERSION, file })?;
+    Ok(recorder)
+  }
+
+  /// Appends `action`, flushing at once so that the log is complete up to a crash.
+  pub fn record(&mut self, action: &Action) -> io::Result<()> {
+    let ms = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    self.write(&Entry { ms, action: action.clone() })
+  }
+
+  fn write(&mut self, line: &impl Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut self.writer, line)?;
+    self.writer.write_all(b"\n")?;
+    self.writer.flush()
+  }
+}
+
+/// A recorded session, read back to be replayed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Log {
+  pub header: Header,
+  pub entries: Vec<Entry>,
+}
+
+impl Log {
+  pub fn read(path: &Path) -> Result<Self> {
+    let file = File::open(path).wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+    Self::parse(BufReader::new(file)).wrap_err_with(|| format!("Failed to replay {}", path.display()))
+  }
+
+  /// Reads a log, skipping blank lines.
+  pub fn parse(reader: impl BufRead) -> Result<Self> {
+    let mut lines = reader.lines().enumerate().filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()));
+    let (i, header) = lines.next().ok_or_else(|| eyre!("The log is empty"))?;
+    let header: Header =
+      serde_json::from_str(&header?).wrap_err_with(|| format!("Invalid header on line {}", i + 1))?;
+    if header.version != VERSION {
+      return Err(eyre!("Unsupported log version {}, expected {VERSION}", header.version));
+    }
+    let entries = lines
+      .map(|(i, line)| serde_json::from_str(&line?).wrap_err_with(|| format!("Invalid entry on line {}", i + 1)))
+      .collect::<Result<Vec<Entry>>>()?;
+    Ok(Self { header, entries })
+  }
+
+  /// Sends the actions at the times they were recorded, counting from now.
+  pub fn replay(self, action_tx: UnboundedSender<Action>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+      let started = Instant::now();
+      for entry in self.entries {
+        tokio::time::sleep_until(started + Duration::from_millis(entry.ms)).await;
+        if action_tx.send(entry.action).is_err() {
+          return;
+        }
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use tokio::sync::mpsc;
+
+  use super::*;
+
+  const FALL: &str = "Down, down, down.\n(Would the fall never come to an end?)";
+
+  #[tokio::test(start_paused = true)]
+  async fn test_record() {
+    let path = std::env::temp_dir().join(format!("sreader-test-{}.jsonl", std::process::id()));
+    let mut recorder = Recorder::create(&path, Some(PathBuf::from("alice.txt"))).unwrap();
+    recorder.record(&Action::TextLoad()).unwrap();
+    tokio::time::advance(Duration::from_millis(1500)).await;
+    recorder.record(&Action::TextPaste(FALL.into())).unwrap();
+    recorder.record(&Action::IncrementText(10)).unwrap();
+    drop(recorder);
+
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+      log.lines().collect::<Vec<_>>(),
+      [
+        r#"{"version":1,"file":"alice.txt"}"#,
+        r#"{"ms":0,"action":"TextLoad()"}"#,
+        r#"{"ms":1500,"action":"TextPaste(Down, down, down.\n(Would the fall never come to an end?))"}"#,
+        r#"{"ms":1500,"action":"IncrementText(10)"}"#,
+      ]
+    );
+    let log = Log::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(log.header, Header { version: VERSION, file: Some(PathBuf::from("alice.txt")) });
+    assert_eq!(
+      log.entries,
+      [
+        Entry { ms: 0, action: Action::TextLoad() },
+        Entry { ms: 1500, action: Action::TextPaste(FALL.into()) },
+        Entry { ms: 1500, action: Action::IncrementText(10) },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_parse() {
+    let parse = |log: &str| Log::parse(log.as_bytes()).map_err(|e| format!("{e:#}"));
+    // The form `Serialize` produces is read as well.
+    let log = parse("{\"version\":1}\n\n{\"ms\":20,\"action\":{\"NextParagraph\":2}}\n").unwrap();
+    assert_eq!(log.header.file, None);
+    assert_eq!(log.entries, [Entry { ms: 20, action: Action::NextParagraph(2) }]);
+
+    assert_eq!(parse(""), Err("The log is empty".to_string()));
+    assert_eq!(parse(r#"{"version":2}"#), Err("Unsupported log version 2, expected 1".to_string()));
+    let err = parse("\n{\"version\":1}\n{\"ms\":0,\"action\":\"Jump\"}").unwrap_err();
+    assert!(err.starts_with("Invalid entry on line 3: Unknown action `Jump`"), "{err}");
+    let err = parse("IncrementText(1)").unwrap_err();
+    assert!(err.starts_with("Invalid header on line 1"), "{err}");
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn test_replay() {
+    let entries = vec![
+      Entry { ms: 0, action: Action::TextLoad() },
+      Entry { ms: 250, action: Action::ScheduleSreadText },
+      Entry { ms: 1000, action: Action::IncrementText(3) },
+    ];
+    let log = Log { header: Header { version: VERSION, file: None }, entries: entries.clone() };
+    let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+    let start = Instant::now();
+    log.replay(action_tx);
+    for entry in entries {
+      assert_eq!(action_rx.recv().await, Some(entry.action));
+      assert_eq!(Instant::now() - start, Duration::from_millis(entry.ms));
+    }
+    assert_eq!(action_rx.recv().await, None);
+  }
+
+  #[test]
+  fn test_is_recorded() {
+    assert!(is_recorded(&Action::ScheduleSreadText));
+    assert!(is_recorded(&Action::SreadText(1)));
+    assert!(!is_recorded(&Action::Render));
+    assert!(!is_recorded(&Action::Quit));
+    assert!(!is_recorded(&Action::Error("Failed to read book.txt".into())));
+  }
+}
@@ -0,0 +1,55 @@
+use std::{collections::HashMap, io, path::PathBuf};
+
+use color_eyre::eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::ReadingOverrides, utils::get_data_dir};
+
+const STATE_FILE: &str = "state.json";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookState {
+  pub position: usize,
+  #[serde(default, skip_serializing_if = "ReadingOverrides::is_empty")]
+  pub overrides: ReadingOverrides,
+}
+
+/// Per-book reading state, persisted as JSON in the data directory.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReadingState {
+  #[serde(default)]
+  pub books: HashMap<String, BookState>,
+}
+
+impl ReadingState {
+  pub fn path() -> PathBuf {
+    get_data_dir().join(STATE_FILE)
+  }
+
+  /// Loads the saved state, which is empty when nothing was saved yet.
+  pub fn load() -> Result<Self> {
+    let path = Self::path();
+    match std::fs::read_to_string(&path) {
+      Ok(json) => serde_json::from_str(&json).wrap_err_with(|| format!("Invalid reading state in {}", path.display())),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+      Err(e) => Err(e).wrap_err_with(|| format!("Failed to read {}", path.display())),
+    }
+  }
+
+  pub fn save(&self) -> Result<()> {
+    let path = Self::path();
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+    Ok(())
+  }
+
+  pub fn book(&self, key: &str) -> Option<&BookState> {
+    self.books.get(key)
+  }
+
+  pub fn book_mut(&mut self, key: &str) -> &mut BookState {
+    self.books.entry(key.to_string()).or_default()
+  }
+}
@@ -0,0 +1,566 @@
+use std::{fs, io, ops::Range, path::Path, sync::Arc};
+
+use icu_segmenter::WordSegmenter;
+use unicode_bidi::{bidi_class, get_base_direction, BidiClass, BidiInfo, Direction, Level};
+use unicode_segmentation::UnicodeSegmentation;
+
+thread_local! {
+  static WORD_SEGMENTER: WordSegmenter = WordSegmenter::new_auto();
+}
+
+/// Token marking the end of a verse line. Never produced by splitting on whitespace.
+pub const LINE_BREAK: &str = "\n";
+/// Token marking the end of a stanza.
+pub const STANZA_BREAK: &str = "\n\n";
+
+/// Size of the first batch `batches` produces, small enough to show the start of a book at once.
+const FIRST_BATCH: usize = 16 * 1024;
+const BATCH: usize = 512 * 1024;
+
+/// The text of a book, which tokens point into.
+pub enum Buffer {
+  Owned(String),
+  /// A file mapped into memory, checked to be UTF-8 when it was opened.
+  #[cfg(feature = "mmap")]
+  Mapped(memmap2::Mmap),
+}
+
+impl Default for Buffer {
+  fn default() -> Self {
+    Buffer::Owned(String::new())
+  }
+}
+
+impl Buffer {
+  /// Reads the file at `path`, or maps it into memory when built with the `mmap` feature.
+  pub fn open(path: &Path) -> io::Result<Self> {
+    if fs::metadata(path)?.len() > u64::from(u32::MAX) {
+      return Err(io::Error::new(io::ErrorKind::InvalidData, "files over 4 GiB are not supported"));
+    }
+    #[cfg(feature = "mmap")]
+    {
+      let file = fs::File::open(path)?;
+      // SAFETY: the map is only read. Like any reader, this assumes the file is not truncated while it is open.
+      let map = unsafe { memmap2::Mmap::map(&file)? };
+      std::str::from_utf8(&map).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+      Ok(Buffer::Mapped(map))
+    }
+    #[cfg(not(feature = "mmap"))]
+    fs::read_to_string(path).map(Buffer::Owned)
+  }
+
+  pub fn as_str(&self) -> &str {
+    match self {
+      Buffer::Owned(text) => text,
+      // SAFETY: checked to be UTF-8 in `open`, and the map is never written to.
+      #[cfg(feature = "mmap")]
+      Buffer::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+    }
+  }
+}
+
+/// A token as a byte range of the source text. Breaks are not in the text, so they start past its end and stand for
+/// `LINE_BREAK` or `STANZA_BREAK` by their length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+  start: u32,
+  len: u32,
+}
+
+impl Span {
+  const BREAK: u32 = u32::MAX;
+
+  /// A span of `token`, which must be a slice of `source`. Sources are at most 4 GiB, see `Buffer::open`.
+  fn of(source: &str, token: &str) -> Self {
+    Span { start: offset(source, token) as u32, len: token.len() as u32 }
+  }
+
+  fn marker(token: &str) -> Self {
+    Span { start: Self::BREAK, len: token.len() as u32 }
+  }
+
+  pub fn resolve<'a>(&self, source: &'a str) -> &'a str {
+    match (self.start, self.len) {
+      (Self::BREAK, 1) => LINE_BREAK,
+      (Self::BREAK, _) => STANZA_BREAK,
+      (start, len) => &source[start as usize..(start + len) as usize],
+    }
+  }
+}
+
+/// Byte offset of `inner`, a slice of `outer`, within `outer`.
+fn offset(outer: &str, inner: &str) -> usize {
+  inner.as_ptr() as usize - outer.as_ptr() as usize
+}
+
+/// The words of a loaded text: spans into one shared buffer rather than a string per word.
+#[derive(Clone, Default)]
+pub struct Text {
+  buffer: Arc<Buffer>,
+  spans: Vec<Span>,
+}
+
+impl Text {
+  pub fn new(buffer: Arc<Buffer>) -> Self {
+    Text { buffer, spans: Vec::new() }
+  }
+
+  pub fn buffer(&self) -> &Arc<Buffer> {
+    &self.buffer
+  }
+
+  pub fn source(&self) -> &str {
+    self.buffer.as_str()
+  }
+
+  pub fn len(&self) -> usize {
+    self.spans.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.spans.is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<&str> {
+    self.spans.get(index).map(|span| span.resolve(self.source()))
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+    self.spans.iter().map(|span| span.resolve(self.source()))
+  }
+
+  /// Adds tokens found in this text's buffer.
+  pub fn extend(&mut self, spans: &[Span]) {
+    self.spans.extend_from_slice(spans);
+  }
+}
+
+impl std::ops::Index<usize> for Text {
+  type Output = str;
+
+  fn index(&self, index: usize) -> &str {
+    self.spans[index].resolve(self.source())
+  }
+}
+
+/// A line of the source text, kept so the context view can show verse as it was written.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceLine {
+  /// Index of the first token on the line.
+  pub start: usize,
+  pub indent: usize,
+}
+
+/// Tokens found by a `Tokenizer`, with indices counted from the start of the text.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tokens {
+  pub spans: Vec<Span>,
+  /// Index of the first token of every paragraph (or stanza).
+  pub paragraphs: Vec<usize>,
+  pub lines: Vec<SourceLine>,
+}
+
+/// Splits text into words a batch of lines at a time, so a long book can be shown before it has all been read.
+/// With `verse`, line ends and stanza breaks become `LINE_BREAK` and `STANZA_BREAK` tokens, except before lines
+/// starting in lower case, which continue a line too long for the page.
+pub struct Tokenizer {
+  verse: bool,
+  new_paragraph: bool,
+  stanza: bool,
+  /// Tokens handed out by `take` so far.
+  count: usize,
+  tokens: Tokens,
+}
+
+impl Tokenizer {
+  pub fn new(verse: bool) -> Self {
+    Tokenizer { verse, new_paragraph: true, stanza: false, count: 0, tokens: Tokens::default() }
+  }
+
+  /// Tokenizes the lines of `source` in `range`, which has to start at the beginning of a line.
+  pub fn feed(&mut self, source: &str, range: Range<usize>) {
+    for line in source[range].lines() {
+      let trimmed = line.trim_start();
+      if trimmed.is_empty() {
+        // A stanza break is only added once the next stanza starts, so the text never ends with one.
+        self.stanza |= self.verse && !self.new_paragraph;
+        self.new_paragraph = true;
+        continue;
+      }
+      if std::mem::take(&mut self.stanza) {
+        self.tokens.spans.push(Span::marker(STANZA_BREAK));
+      } else if self.verse && !self.new_paragraph && !trimmed.starts_with(char::is_lowercase) {
+        self.tokens.spans.push(Span::marker(LINE_BREAK));
+      }
+      let index = self.count + self.tokens.spans.len();
+      if std::mem::take(&mut self.new_paragraph) {
+        self.tokens.paragraphs.push(index);
+      }
+      self.tokens.lines.push(SourceLine { start: index, indent: line.len() - trimmed.len() });
+      let words = line.split_whitespace().flat_map(split_dashes).flat_map(split_run);
+      self.tokens.spans.extend(words.map(|word| Span::of(source, word)));
+    }
+  }
+
+  /// The tokens found since the last call.
+  pub fn take(&mut self) -> Tokens {
+    self.count += self.tokens.spans.len();
+    std::mem::take(&mut self.tokens)
+  }
+}
+
+/// Splits all of `text` into words, see `Tokenizer`.
+pub fn tokenize(text: &str, verse: bool) -> Tokens {
+  let mut tokenizer = Tokenizer::new(verse);
+  tokenizer.feed(text, 0..text.len());
+  tokenizer.take()
+}
+
+/// Tokenizes `source` in batches cut at line ends, starting with a small one so the first words are ready at once.
+pub fn batches(source: &str, verse: bool) -> impl Iterator<Item = Tokens> + '_ {
+  batches_of(source, verse, FIRST_BATCH, BATCH)
+}
+
+fn batches_of(source: &str, verse: bool, first: usize, size: usize) -> impl Iterator<Item = Tokens> + '_ {
+  let mut tokenizer = Tokenizer::new(verse);
+  let (mut start, mut next) = (0, first);
+  std::iter::from_fn(move || {
+    if start >= source.len() {
+      return None;
+    }
+    // Cutting at a newline byte is safe, it never occurs inside a multi-byte character.
+    let cut = (start + next).min(source.len());
+    let end = source.as_bytes()[cut..].iter().position(|&b| b == b'\n').map_or(source.len(), |i| cut + i + 1);
+    tokenizer.feed(source, start..end);
+    (start, next) = (end, size);
+    Some(tokenizer.take())
+  })
+}
+
+pub fn is_break(token: &str) -> bool {
+  token == LINE_BREAK || token == STANZA_BREAK
+}
+
+/// Whether a paragraph starting with `token` is a chapter heading, e.g. `CHAPTER IV.` or `Chapter 4`.
+pub fn is_chapter(token: &str) -> bool {
+  matches!(token, "CHAPTER" | "Chapter")
+}
+
+/// Tokens that are read as a whole: they are never hyphenated, and a full stop in them does not end a sentence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+  Word,
+  Abbreviation,
+  Number,
+  Url,
+  Email,
+}
+
+/// Classifies `token`, looking past quotes and brackets around it and punctuation after it.
+pub fn token_kind(token: &str, abbreviations: &[String]) -> TokenKind {
+  let core = token.trim_start_matches(is_opening).trim_end_matches(is_closing);
+  if abbreviations.iter().any(|a| a.eq_ignore_ascii_case(core.trim_end_matches([',', ';', ':']))) {
+    return TokenKind::Abbreviation;
+  }
+  let core = core.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+  let lower = core.to_lowercase();
+  if ["http://", "https://", "ftp://", "www."].iter().any(|scheme| lower.starts_with(scheme)) {
+    TokenKind::Url
+  } else if core.split_once('@').is_some_and(|(user, host)| !user.is_empty() && host.contains('.')) {
+    TokenKind::Email
+  } else if is_number(core) {
+    TokenKind::Number
+  } else {
+    TokenKind::Word
+  }
+}
+
+/// Numbers such as `3.14`, `1,000,000`, `-5`, `$20`, `50%` or `12:30`.
+fn is_number(core: &str) -> bool {
+  let digits = core.trim_start_matches(['+', '-', '−', '$', '€', '£', '¥']).trim_end_matches('%');
+  digits.starts_with(|c: char| c.is_ascii_digit())
+    && digits.ends_with(|c: char| c.is_ascii_digit())
+    && digits.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | ':' | '/'))
+}
+
+/// Splits words joined by an em dash, written `—` or `--`, keeping the dash at the end of the first word. URLs and
+/// email addresses are left alone.
+fn split_dashes(run: &str) -> Vec<&str> {
+  let joined = run.contains('—') || run.contains("--");
+  if !joined || matches!(token_kind(run, &[]), TokenKind::Url | TokenKind::Email) {
+    return vec![run];
+  }
+  let mut words: Vec<Range<usize>> = Vec::new();
+  let mut pending = None;
+  let mut start = 0;
+  while start < run.len() {
+    let rest = &run[start..];
+    let end = [rest.find('—'), rest.find("--")].into_iter().flatten().min().unwrap_or(rest.len());
+    let dashes = rest[end..].len() - rest[end..].trim_start_matches(['—', '-']).len();
+    let word = start..start + end + dashes;
+    // Dashes without a word of their own, as in `—word` or `word—”`, stay with their neighbour.
+    if run[word.clone()].contains(char::is_alphanumeric) {
+      words.push(pending.take().unwrap_or(word.start)..word.end);
+    } else if let Some(last) = words.last_mut() {
+      last.end = word.end;
+    } else {
+      pending.get_or_insert(word.start);
+    }
+    start = word.end;
+  }
+  words.extend(pending.map(|start| start..run.len()));
+  words.into_iter().map(|word| &run[word]).collect()
+}
+
+/// Splits a run of text between spaces into words. Only runs in scripts written without spaces go through the
+/// Unicode word segmenter, so hyphenated compounds and contractions in other scripts stay whole.
+fn split_run(run: &str) -> Vec<&str> {
+  if !run.contains(is_spaceless) {
+    return vec![run];
+  }
+  let mut words: Vec<Range<usize>> = Vec::new();
+  let mut pending = None;
+  WORD_SEGMENTER.with(|segmenter| {
+    let mut start = 0;
+    for end in segmenter.segment_str(run) {
+      if end == 0 {
+        continue;
+      }
+      // Punctuation rides along with the word it belongs to, so pauses still see it. The segmenter's own word
+      // types are not set for dictionary matches, so letters decide what counts as a word.
+      if run[start..end].contains(char::is_alphanumeric) {
+        words.push(pending.take().unwrap_or(start)..end);
+      } else if let (Some(last), None, false) = (words.last_mut(), pending, run[start..].starts_with(is_opening)) {
+        last.end = end;
+      } else {
+        pending.get_or_insert(start);
+      }
+      start = end;
+    }
+  });
+  match (words.last_mut(), pending) {
+    (Some(last), Some(_)) => last.end = run.len(),
+    (None, Some(start)) => words.push(start..run.len()),
+    _ => {},
+  }
+  words.into_iter().map(|word| &run[word]).collect()
+}
+
+fn is_opening(c: char) -> bool {
+  matches!(c, '「' | '『' | '（' | '【' | '《' | '〈' | '(' | '[' | '<' | '“' | '‘' | '"' | '\'')
+}
+
+fn is_closing(c: char) -> bool {
+  matches!(c, '」' | '』' | '）' | '】' | '》' | '〉' | ')' | ']' | '>' | '”' | '’' | '"' | '\'' | '_')
+}
+
+/// Whether `c` belongs to a script written without spaces between words, such as Chinese, Japanese or Thai.
+pub fn is_spaceless(c: char) -> bool {
+  matches!(c as u32,
+    0x0E00..=0x0EFF // Thai, Lao
+    | 0x1000..=0x109F // Myanmar
+    | 0x1780..=0x17FF // Khmer
+    | 0x3000..=0x30FF // CJK punctuation, Hiragana, Katakana
+    | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F // Han
+    | 0xFF00..=0xFFEF // Full-width forms
+  )
+}
+
+/// What goes between two adjacent tokens when they are shown together: nothing within spaceless text.
+pub fn separator(previous: &str, next: &str) -> &'static str {
+  match previous.ends_with(is_spaceless) && next.starts_with(is_spaceless) {
+    true => "",
+    false => " ",
+  }
+}
+
+/// Joins tokens back into text, see `separator`.
+pub fn join<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+  let mut text = String::new();
+  let mut previous: Option<&str> = None;
+  for word in words {
+    if let Some(previous) = previous {
+      text.push_str(separator(previous, word));
+    }
+    text.push_str(word);
+    previous = Some(word);
+  }
+  text
+}
+
+/// The grapheme clusters of `word` in display order, each with its position in reading order. Terminals do not
+/// reorder text themselves, so Arabic and Hebrew have to be laid out right to left here.
+pub fn visual_order(word: &str) -> Vec<(usize, &str)> {
+  let graphemes: Vec<(usize, &str)> = word.graphemes(true).enumerate().collect();
+  if !word.chars().any(|c| matches!(bidi_class(c), BidiClass::R | BidiClass::AL)) {
+    return graphemes;
+  }
+  let bidi = BidiInfo::new(word, None);
+  let levels: Vec<Level> = word.grapheme_indices(true).map(|(byte, _)| bidi.levels[byte]).collect();
+  BidiInfo::reorder_visual(&levels).into_iter().map(|i| graphemes[i]).collect()
+}
+
+/// Whether `text` reads right to left, judging by its first letter with a direction.
+pub fn is_rtl(text: &str) -> bool {
+  get_base_direction(text) == Direction::Rtl
+}
+
+/// Guesses whether `text` is verse. Verse has short lines, or lines that end in punctuation and start with a
+/// capital even in the middle of a stanza, where wrapped prose mostly breaks mid-sentence.
+pub fn is_verse(text: &str) -> bool {
+  let mut lengths = Vec::new();
+  let (mut inner, mut capitalized, mut punctuated) = (0, 0, 0);
+  let mut previous_blank = true;
+  for line in text.lines().map(str::trim) {
+    if line.is_empty() {
+      previous_blank = true;
+      continue;
+    }
+    lengths.push(line.chars().count());
+    if !previous_blank {
+      inner += 1;
+      capitalized += usize::from(line.starts_with(char::is_uppercase));
+    }
+    let end = line.trim_end_matches(['"', '\'', ')', '’', '”']);
+    punctuated += usize::from(end.ends_with([',', '.', ';', ':', '!', '?']));
+    previous_blank = false;
+  }
+  if lengths.is_empty() {
+    return false;
+  }
+  lengths.sort_unstable();
+  let short_lines = lengths[lengths.len() / 2] < 40;
+  let line_shaped = inner > 0 && capitalized * 2 > inner && punctuated * 2 > lengths.len();
+  short_lines || line_shaped
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  const VERSE: &str = "  I celebrate myself, and sing myself,\n  And what I assume you shall assume,\n  For every atom \
+                       belonging to me as good\n      belongs to you.\n\n  I loafe and invite my soul,\n";
+
+  const PROSE: &str = "Alice was beginning to get very tired of sitting by her sister on the\nbank, and of having \
+                       nothing to do: once or twice she had peeped into the\nbook her sister was reading, but it \
+                       had no pictures or conversations in\nit.\n";
+
+  fn words(text: &str, verse: bool) -> Vec<&str> {
+    tokenize(text, verse).spans.iter().map(|span| span.resolve(text)).collect()
+  }
+
+  #[test]
+  fn test_tokenize_prose() {
+    let tokens = tokenize("One two\nthree.\n\nFour", false);
+    assert_eq!(words("One two\nthree.\n\nFour", false), vec!["One", "two", "three.", "Four"]);
+    assert_eq!(tokens.paragraphs, vec![0, 3]);
+    assert_eq!(tokens.lines, vec![
+      SourceLine { start: 0, indent: 0 },
+      SourceLine { start: 2, indent: 0 },
+      SourceLine { start: 3, indent: 0 }
+    ]);
+  }
+
+  #[test]
+  fn test_tokenize_verse() {
+    let tokens = tokenize(VERSE, true);
+    let words = words(VERSE, true);
+    assert_eq!(&words[..7], &["I", "celebrate", "myself,", "and", "sing", "myself,", LINE_BREAK]);
+    assert_eq!(&words[22..26], &["good", "belongs", "to", "you."]);
+    assert_eq!(words[26], STANZA_BREAK);
+    assert_eq!(words.last(), Some(&"soul,"));
+    assert_eq!(tokens.paragraphs, vec![0, 27]);
+    assert_eq!(tokens.lines[3], SourceLine { start: 23, indent: 6 });
+  }
+
+  #[test]
+  fn test_batches() {
+    let text = format!("{VERSE}\n\n{VERSE}");
+    let mut joined = Tokens::default();
+    for batch in batches_of(&text, true, 10, 60) {
+      joined.spans.extend(batch.spans);
+      joined.paragraphs.extend(batch.paragraphs);
+      joined.lines.extend(batch.lines);
+    }
+    assert_eq!(joined, tokenize(&text, true));
+    assert_eq!(batches(&text, true).count(), 1);
+    assert_eq!(batches("", true).count(), 0);
+  }
+
+  #[test]
+  fn test_text() {
+    let buffer = Buffer::Owned("Curiouser and curiouser!".to_string());
+    let mut text = Text::new(Arc::new(buffer));
+    text.extend(&tokenize(text.source(), false).spans);
+    assert_eq!(text.len(), 3);
+    assert_eq!(&text[2], "curiouser!");
+    assert_eq!(join(text.iter()), "Curiouser and curiouser!");
+    assert_eq!(std::mem::size_of::<Span>(), 8);
+  }
+
+  #[test]
+  fn test_tokenize_cjk() {
+    let words = |text| words(text, false);
+    assert_eq!(words("我们今天去公园。"), vec!["我们", "今天", "去", "公园。"]);
+    let japanese = vec!["「吾輩", "は", "猫", "で", "ある。」", "名前"];
+    assert_eq!(words("「吾輩は猫である。」名前"), japanese);
+    assert_eq!(words("waistcoat-pocket don't"), vec!["waistcoat-pocket", "don't"]);
+    assert_eq!(join(words("Alice 我们今天 went")), "Alice 我们今天 went");
+  }
+
+  #[test]
+  fn test_tokenize_dashes() {
+    let words = |text| words(text, false);
+    assert_eq!(words("Hatter—extraordinary"), vec!["Hatter—", "extraordinary"]);
+    assert_eq!(words("said--\"what"), vec!["said--", "\"what"]);
+    assert_eq!(words("—Alice and sister—”"), vec!["—Alice", "and", "sister—”"]);
+    assert_eq!(words("well-known -- www.a--b.com"), vec!["well-known", "--", "www.a--b.com"]);
+  }
+
+  #[test]
+  fn test_token_kind() {
+    let abbreviations = vec!["Mr.".to_string(), "e.g.".to_string()];
+    let kind = |token| token_kind(token, &abbreviations);
+    assert_eq!(kind("mr."), TokenKind::Abbreviation);
+    assert_eq!(kind("(e.g.,"), TokenKind::Abbreviation);
+    assert_eq!(kind("end."), TokenKind::Word);
+    assert_eq!(kind("3.14."), TokenKind::Number);
+    assert_eq!(kind("1,000,000"), TokenKind::Number);
+    assert_eq!(kind("$20,"), TokenKind::Number);
+    assert_eq!(kind("1990s"), TokenKind::Word);
+    assert_eq!(kind("https://example.com/a-b."), TokenKind::Url);
+    assert_eq!(kind("<alice@example.org>"), TokenKind::Email);
+    assert_eq!(kind("@alice"), TokenKind::Word);
+  }
+
+  #[test]
+  fn test_visual_order() {
+    let visual = |word| visual_order(word).into_iter().map(|(_, g)| g).collect::<String>();
+    assert_eq!(visual("word,"), "word,");
+    assert_eq!(visual("שלום,"), ",םולש");
+    assert_eq!(visual_order("שלום")[3], (0, "ש"));
+    assert_eq!(visual_order("é").len(), 1);
+    assert!(is_rtl("مرحبا بالعالم"));
+    assert!(!is_rtl("Alice"));
+  }
+
+  #[test]
+  fn test_is_chapter() {
+    let source = "CHAPTER I.\nDown the Rabbit-Hole\n\nAlice was beginning\n\nChapter 2\n\nchapter and verse";
+    let mut text = Text::new(Arc::new(Buffer::Owned(source.to_string())));
+    let tokens = tokenize(text.source(), false);
+    text.extend(&tokens.spans);
+    let headings: Vec<usize> = tokens.paragraphs.into_iter().filter(|&p| is_chapter(&text[p])).collect();
+    assert_eq!(headings, vec![0, 8]);
+  }
+
+  #[test]
+  fn test_is_verse() {
+    assert!(is_verse(VERSE));
+    assert!(!is_verse(PROSE));
+    assert!(!is_verse(""));
+    assert!(is_verse("Hope is the thing\nwith feathers\nThat perches\nin the soul"));
+  }
+}
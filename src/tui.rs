@@ -1,24 +1,101 @@
 use std::{
+  io,
   ops::{Deref, DerefMut},
+  sync::Arc,
   time::Duration,
 };
 
 use color_eyre::eyre::Result;
 use crossterm::{
   cursor,
-  event::{Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent},
+  event::{
+    DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange, Event as CrosstermEvent,
+    KeyEvent, KeyEventKind, MouseEvent,
+  },
   terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
-use ratatui::backend::CrosstermBackend as Backend;
+#[cfg(test)]
+use ratatui::backend::TestBackend;
+use ratatui::{
+  backend::{ClearType, CrosstermBackend},
+  buffer::Cell,
+  layout::Rect,
+};
 use serde::{Deserialize, Serialize};
 use tokio::{
-  sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+  sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+    Notify,
+  },
   task::JoinHandle,
+  time::MissedTickBehavior,
 };
 use tokio_util::sync::CancellationToken;
 
-pub type Frame<'a> = ratatui::Frame<'a, Backend<std::io::Stderr>>;
+pub type Frame<'a> = ratatui::Frame<'a, Backend>;
+
+/// What the terminal is drawn with: the real terminal, or in tests a buffer in memory, see `Tui::test`.
+pub enum Backend {
+  Crossterm(CrosstermBackend<io::Stderr>),
+  #[cfg(test)]
+  Test(TestBackend),
+}
+
+macro_rules! delegate {
+  ($self:ident, $backend:ident => $call:expr) => {
+    match $self {
+      Backend::Crossterm($backend) => $call,
+      #[cfg(test)]
+      Backend::Test($backend) => $call,
+    }
+  };
+}
+
+impl ratatui::backend::Backend for Backend {
+  fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+  where
+    I: Iterator<Item = (u16, u16, &'a Cell)>,
+  {
+    delegate!(self, backend => backend.draw(content))
+  }
+
+  fn append_lines(&mut self, n: u16) -> io::Result<()> {
+    delegate!(self, backend => backend.append_lines(n))
+  }
+
+  fn hide_cursor(&mut self) -> io::Result<()> {
+    delegate!(self, backend => backend.hide_cursor())
+  }
+
+  fn show_cursor(&mut self) -> io::Result<()> {
+    delegate!(self, backend => backend.show_cursor())
+  }
+
+  fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+    delegate!(self, backend => backend.get_cursor())
+  }
+
+  fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+    delegate!(self, backend => backend.set_cursor(x, y))
+  }
+
+  fn clear(&mut self) -> io::Result<()> {
+    delegate!(self, backend => backend.clear())
+  }
+
+  fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+    delegate!(self, backend => backend.clear_region(clear_type))
+  }
+
+  fn size(&self) -> io::Result<Rect> {
+    delegate!(self, backend => backend.size())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    delegate!(self, backend => backend.flush())
+  }
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Event {
@@ -37,24 +114,50 @@ pub enum Event {
 }
 
 pub struct Tui {
-  pub terminal: ratatui::Terminal<Backend<std::io::Stderr>>,
+  pub terminal: ratatui::Terminal<Backend>,
   pub task: JoinHandle<()>,
   pub cancellation_token: CancellationToken,
   pub event_rx: UnboundedReceiver<Event>,
   pub event_tx: UnboundedSender<Event>,
   pub frame_rate: f64,
   pub tick_rate: f64,
+  /// Set by `request_render` and cleared when `Event::Render` is sent, so frames are only rendered after something
+  /// changed and never more often than `frame_rate`.
+  pub render_requested: Arc<Notify>,
+  /// Renders every frame whether or not anything changed, for comparing the frame rate.
+  pub always_render: bool,
 }
 
 impl Tui {
   pub fn new() -> Result<Self> {
+    Self::with_backend(Backend::Crossterm(CrosstermBackend::new(io::stderr())))
+  }
+
+  /// A terminal of `width` by `height` cells in memory, which is never entered: events are sent to it by hand.
+  #[cfg(test)]
+  pub fn test(width: u16, height: u16) -> Result<Self> {
+    Self::with_backend(Backend::Test(TestBackend::new(width, height)))
+  }
+
+  fn with_backend(backend: Backend) -> Result<Self> {
     let tick_rate = 4.0;
     let frame_rate = 60.0;
-    let terminal = ratatui::Terminal::new(Backend::new(std::io::stderr()))?;
+    let terminal = ratatui::Terminal::new(backend)?;
     let (event_tx, event_rx) = mpsc::unbounded_channel();
     let cancellation_token = CancellationToken::new();
     let task = tokio::spawn(async {});
-    Ok(Self { terminal, task, cancellation_token, event_rx, event_tx, frame_rate, tick_rate })
+    let render_requested = Arc::new(Notify::new());
+    Ok(Self {
+      terminal,
+      task,
+      cancellation_token,
+      event_rx,
+      event_tx,
+      frame_rate,
+      tick_rate,
+      render_requested,
+      always_render: false,
+    })
   }
 
   pub fn tick_rate(&mut self, tick_rate: f64) {
@@ -65,6 +168,15 @@ impl Tui {
     self.frame_rate = frame_rate;
   }
 
+  pub fn always_render(&mut self, always_render: bool) {
+    self.always_render = always_render;
+  }
+
+  /// Asks for an `Event::Render` at the next frame.
+  pub fn request_render(&self) {
+    self.render_requested.notify_one();
+  }
+
   pub fn start(&mut self) {
     let tick_delay = std::time::Duration::from_secs_f64(1.0 / self.tick_rate);
     let render_delay = std::time::Duration::from_secs_f64(1.0 / self.frame_rate);
@@ -72,14 +184,20 @@ impl Tui {
     self.cancellation_token = CancellationToken::new();
     let _cancellation_token = self.cancellation_token.clone();
     let _event_tx = self.event_tx.clone();
+    let render_requested = self.render_requested.clone();
+    let always_render = self.always_render;
     self.task = tokio::spawn(async move {
       let mut reader = crossterm::event::EventStream::new();
       let mut tick_interval = tokio::time::interval(tick_delay);
       let mut render_interval = tokio::time::interval(render_delay);
+      // After idling the first requested frame is rendered straight away rather than catching up on missed ones.
+      render_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+      let mut render_pending = always_render;
       _event_tx.send(Event::Init).unwrap();
       loop {
         let tick_delay = tick_interval.tick();
         let render_delay = render_interval.tick();
+        let render_request = render_requested.notified();
         let crossterm_event = reader.next().fuse();
         tokio::select! {
           _ = _cancellation_token.cancelled() => {
@@ -120,7 +238,11 @@ impl Tui {
           _ = tick_delay => {
               _event_tx.send(Event::Tick).unwrap();
           },
-          _ = render_delay => {
+          _ = render_request, if !render_pending => {
+              render_pending = true;
+          },
+          _ = render_delay, if render_pending => {
+              render_pending = always_render;
               _event_tx.send(Event::Render).unwrap();
           },
         }
@@ -147,7 +269,13 @@ impl Tui {
 
   pub fn enter(&mut self) -> Result<()> {
     crossterm::terminal::enable_raw_mode()?;
-    crossterm::execute!(std::io::stderr(), EnterAlternateScreen, cursor::Hide)?;
+    crossterm::execute!(
+      std::io::stderr(),
+      EnterAlternateScreen,
+      EnableFocusChange,
+      EnableBracketedPaste,
+      cursor::Hide
+    )?;
     self.start();
     Ok(())
   }
@@ -156,7 +284,13 @@ impl Tui {
     self.stop()?;
     if crossterm::terminal::is_raw_mode_enabled()? {
       self.flush()?;
-      crossterm::execute!(std::io::stderr(), LeaveAlternateScreen, cursor::Show)?;
+      crossterm::execute!(
+        std::io::stderr(),
+        DisableBracketedPaste,
+        DisableFocusChange,
+        LeaveAlternateScreen,
+        cursor::Show
+      )?;
       crossterm::terminal::disable_raw_mode()?;
     }
     Ok(())
@@ -184,7 +318,7 @@ impl Tui {
 }
 
 impl Deref for Tui {
-  type Target = ratatui::Terminal<Backend<std::io::Stderr>>;
+  type Target = ratatui::Terminal<Backend>;
 
   fn deref(&self) -> &Self::Target {
     &self.terminal
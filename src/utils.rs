@@ -1,24 +1,35 @@
-use std::path::PathBuf;
+use std::{
+  collections::VecDeque,
+  fmt,
+  path::PathBuf,
+  sync::{Arc, Mutex},
+};
 
 use color_eyre::eyre::Result;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use tracing::error;
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{self, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, Layer};
+use tracing::{field::Field, Event, Level, Subscriber};
+use tracing_subscriber::{
+  self, layer::Context, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+};
 
-pub static GIT_COMMIT_HASH: &'static str = env!("RATATUI_COUNTER_GIT_INFO");
+pub static GIT_COMMIT_HASH: &str = env!("RATATUI_COUNTER_GIT_INFO");
 
 lazy_static! {
   pub static ref PROJECT_NAME: String = env!("CARGO_CRATE_NAME").to_uppercase().to_string();
-  pub static ref DATA_FOLDER: Option<PathBuf> =
-    std::env::var(format!("{}_DATA", PROJECT_NAME.clone())).ok().map(PathBuf::from);
-  pub static ref CONFIG_FOLDER: Option<PathBuf> =
-    std::env::var(format!("{}_CONFIG", PROJECT_NAME.clone())).ok().map(PathBuf::from);
+  pub static ref DATA_FOLDER: Option<PathBuf> = folder_override("DATA", "data");
+  pub static ref CONFIG_FOLDER: Option<PathBuf> = folder_override("CONFIG", "config");
   pub static ref LOG_ENV: String = format!("{}_LOGLEVEL", PROJECT_NAME.clone());
   pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
+  /// The most recent log records, for the log pane.
+  pub static ref LOG_RECORDS: Arc<Mutex<LogRecords>> = Arc::default();
 }
 
+/// Log records kept for the log pane.
+const LOG_RECORDS_LIMIT: usize = 500;
+
 fn project_directory() -> Option<ProjectDirs> {
   ProjectDirs::from("com", "kdheepak", env!("CARGO_PKG_NAME"))
 }
@@ -71,6 +82,37 @@ pub fn initialize_panic_handler() -> Result<()> {
   Ok(())
 }
 
+/// The folder given by the `{PROJECT_NAME}_{variable}` environment variable.
+#[cfg(not(test))]
+fn folder_override(variable: &str, _test_folder: &str) -> Option<PathBuf> {
+  std::env::var(format!("{}_{variable}", PROJECT_NAME.clone())).ok().map(PathBuf::from)
+}
+
+/// Tests use a folder of their own, so that they neither read the user's config nor overwrite their reading positions.
+#[cfg(test)]
+fn folder_override(_variable: &str, test_folder: &str) -> Option<PathBuf> {
+  Some(test_dir().join(test_folder))
+}
+
+/// The folder of this test run, which is removed again when the run exits.
+#[cfg(test)]
+fn test_dir() -> PathBuf {
+  use std::sync::Once;
+
+  static REMOVE_AT_EXIT: Once = Once::new();
+  extern "C" fn remove() {
+    std::fs::remove_dir_all(test_dir_path()).ok();
+  }
+  fn test_dir_path() -> PathBuf {
+    std::env::temp_dir().join(format!("{}-test-{}", env!("CARGO_PKG_NAME"), std::process::id()))
+  }
+  // SAFETY: `remove` is a function without state, so it can be called at any point until the process is gone.
+  REMOVE_AT_EXIT.call_once(|| unsafe {
+    libc::atexit(remove);
+  });
+  test_dir_path()
+}
+
 pub fn get_data_dir() -> PathBuf {
   let directory = if let Some(s) = DATA_FOLDER.clone() {
     s
@@ -93,6 +135,19 @@ pub fn get_config_dir() -> PathBuf {
   directory
 }
 
+/// Socket for remote control, see `remote::Remote`: in the runtime directory where there is one, otherwise the data
+/// directory.
+pub fn get_socket_path() -> PathBuf {
+  let directory = match project_directory().and_then(|dirs| dirs.runtime_dir().map(|dir| dir.to_path_buf())) {
+    Some(dir) => dir,
+    None => get_data_dir(),
+  };
+  directory.join(format!("{}.sock", env!("CARGO_PKG_NAME")))
+}
+
+/// Logs to `LOG_FILE` in the data directory and to `LOG_RECORDS`, at the level `RUST_LOG` or `LOG_ENV` give, `info`
+/// by default. Records of the `log` crate are included. With the `console` feature, task data is served to
+/// tokio-console as well.
 pub fn initialize_logging() -> Result<()> {
   let directory = get_data_dir();
   std::fs::create_dir_all(directory.clone())?;
@@ -110,11 +165,89 @@ pub fn initialize_logging() -> Result<()> {
     .with_writer(log_file)
     .with_target(false)
     .with_ansi(false)
-    .with_filter(tracing_subscriber::filter::EnvFilter::from_default_env());
-  tracing_subscriber::registry().with(file_subscriber).with(ErrorLayer::default()).init();
+    .with_filter(EnvFilter::from_default_env());
+  let records_subscriber = RecordsLayer::new(LOG_RECORDS.clone()).with_filter(EnvFilter::from_default_env());
+  let registry = tracing_subscriber::registry().with(file_subscriber).with(records_subscriber);
+  #[cfg(feature = "console")]
+  let registry = registry.with(console_subscriber::spawn());
+  registry.with(ErrorLayer::default()).init();
   Ok(())
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+  pub level: Level,
+  pub target: String,
+  /// The message followed by the other fields, e.g. `Reloaded configuration path=config.json5`.
+  pub message: String,
+}
+
+/// The last `LOG_RECORDS_LIMIT` records, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct LogRecords {
+  pub records: VecDeque<LogRecord>,
+  /// Records seen so far, including those that were dropped, to tell when there are new ones.
+  pub total: u64,
+}
+
+impl LogRecords {
+  pub fn push(&mut self, record: LogRecord) {
+    self.records.push_back(record);
+    if self.records.len() > LOG_RECORDS_LIMIT {
+      self.records.pop_front();
+    }
+    self.total += 1;
+  }
+}
+
+/// Keeps the events it sees in `LogRecords`.
+pub struct RecordsLayer {
+  records: Arc<Mutex<LogRecords>>,
+}
+
+impl RecordsLayer {
+  pub fn new(records: Arc<Mutex<LogRecords>>) -> Self {
+    Self { records }
+  }
+}
+
+impl<S: Subscriber> Layer<S> for RecordsLayer {
+  fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+    let metadata = event.metadata();
+    let mut visitor = RecordVisitor { target: metadata.target().to_string(), ..RecordVisitor::default() };
+    event.record(&mut visitor);
+    let record = LogRecord { level: *metadata.level(), target: visitor.target, message: visitor.message };
+    if let Ok(mut records) = self.records.lock() {
+      records.push(record);
+    }
+  }
+}
+
+#[derive(Default)]
+struct RecordVisitor {
+  target: String,
+  message: String,
+}
+
+impl tracing::field::Visit for RecordVisitor {
+  fn record_str(&mut self, field: &Field, value: &str) {
+    match field.name() {
+      // Records of the `log` crate come with their target as a field, the event's own is just `log`.
+      "log.target" => self.target = value.to_string(),
+      "message" => self.message.insert_str(0, value),
+      _ => self.record_debug(field, &value),
+    }
+  }
+
+  fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+    if field.name() == "message" {
+      self.message.insert_str(0, &format!("{value:?}"));
+    } else if !field.name().starts_with("log.") {
+      self.message.push_str(&format!(" {}={value:?}", field.name()));
+    }
+  }
+}
+
 /// Similar to the `std::dbg!` macro, but generates `tracing` events rather
 /// than printing to stdout.
 ///